@@ -0,0 +1,19 @@
+use std::collections::BTreeMap;
+use zellij_tile::prelude::{Event, PipeMessage};
+
+/// Room as zellij drives it, minus the printing: [`crate::State`] implements it and the plugin's
+/// `ZellijPlugin` only hands over to it. What it asks of zellij goes through [`crate::host`], so
+/// outside of zellij a test can feed it events of its own and read back the frames it drew along
+/// with the commands it issued.
+pub trait App {
+    fn load(&mut self, configuration: BTreeMap<String, String>);
+
+    /// Handles `event`, returning whether the frame changed.
+    fn update(&mut self, event: Event) -> bool;
+
+    /// Handles a pipe `message`, returning whether the frame changed.
+    fn pipe(&mut self, message: PipeMessage) -> bool;
+
+    /// The frame for a pane of `rows` by `cols`, as it would be printed.
+    fn render(&mut self, rows: usize, cols: usize) -> String;
+}
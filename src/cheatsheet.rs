@@ -0,0 +1,43 @@
+use zellij_tile::prelude::*;
+
+/// One line of the cheatsheet, every key bound to the same actions in the same mode.
+pub struct Binding {
+    pub mode: InputMode,
+    pub actions: String,
+    pub keys: String,
+}
+
+impl Binding {
+    /// What the filter is matched against.
+    pub fn search_str(&self) -> String {
+        format!("{:?} {} {}", self.mode, self.actions, self.keys).to_lowercase()
+    }
+}
+
+/// Zellij's keybindings as `ModeUpdate` sends them, grouped by mode and actions.
+pub fn bindings(keybinds: &KeybindsVec) -> Vec<Binding> {
+    let mut bindings: Vec<Binding> = Vec::new();
+    for (mode, keys) in keybinds {
+        for (key, actions) in keys {
+            let actions = actions
+                .iter()
+                .map(|action| format!("{:?}", action))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            match bindings
+                .iter_mut()
+                .find(|binding| binding.mode == *mode && binding.actions == actions)
+            {
+                Some(binding) => binding.keys.push_str(&format!(" / {}", key)),
+                None => bindings.push(Binding {
+                    mode: *mode,
+                    actions,
+                    keys: key.to_string(),
+                }),
+            }
+        }
+    }
+
+    bindings
+}
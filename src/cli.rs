@@ -0,0 +1,112 @@
+use crate::host;
+use crate::log;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Marks the scripts moving tabs, to tell their results apart from other commands'.
+pub const CONTEXT: &str = "cli";
+/// Marks the command writing a torn off tab's layout, holding the tab's name.
+pub const TEAR_OFF_CONTEXT: &str = "tear_off";
+
+/// Moves the tab at `from` to `to` (both 0 indexed) then focuses `back`, the position the tab to
+/// come back to has once the move is done. Plugins can't move tabs, zellij only offers that to
+/// its CLI one step at a time, so the whole walk runs as a single script in `session`.
+pub fn move_tab(session: &str, from: usize, to: usize, back: usize) {
+    run(session, &format!("{} ; {}", walk(from, to), go_to(back)));
+}
+
+/// Swaps the tabs at `a` and `b` (0 indexed, `a` first) with two moves, then focuses `back`
+/// like [`move_tab`].
+pub fn swap_tabs(session: &str, a: usize, b: usize, back: usize) {
+    // once `a` walked over to `b`, the tab that was at `b` sits right before it
+    let script = format!("{} && {} ; {}", walk(a, b), walk(b - 1, a), go_to(back));
+    run(session, &script);
+}
+
+/// The commands moving the tab at `from` to `to` one step at a time.
+fn walk(from: usize, to: usize) -> String {
+    let direction = if to > from { "right" } else { "left" };
+    let mut script = go_to(from);
+    for _ in 0..from.abs_diff(to) {
+        script.push_str(&format!(" && zellij action move-tab {}", direction));
+    }
+    script
+}
+
+fn go_to(position: usize) -> String {
+    format!("zellij action go-to-tab {}", position + 1)
+}
+
+/// Runs `script` against `session`, as a single command so its steps can't interleave with
+/// another script's.
+fn run(session: &str, script: &str) {
+    log::action(&format!("run script={:?}", script));
+    host::run_command_with_env_variables_and_cwd(
+        &["sh", "-c", script],
+        BTreeMap::from([("ZELLIJ_SESSION_NAME".to_string(), session.to_string())]),
+        PathBuf::from("."),
+        BTreeMap::from([(CONTEXT.to_string(), String::new())]),
+    );
+}
+
+/// Moves each tab of `positions` (0 indexed, in the order given) one step to the right (or the
+/// left) then focuses `back`, like [`move_tab`].
+pub fn shift_tabs(session: &str, positions: &[usize], right: bool, back: usize) {
+    let mut script = positions
+        .iter()
+        .map(|position| walk(*position, if right { position + 1 } else { position - 1 }))
+        .collect::<Vec<_>>()
+        .join(" && ");
+    script.push_str(&format!(" ; {}", go_to(back)));
+    run(session, &script);
+}
+
+/// Writes the layout `kdl` of the session a tab named `name` is torn off to in a temporary file
+/// of the host, whose path the command prints. Sessions only take layouts from files, and the
+/// plugin's own `/data` is somewhere zellij doesn't say.
+pub fn write_layout(name: &str, kdl: &str) {
+    log::action(&format!("write layout session={:?}", name));
+    host::run_command(
+        &[
+            "sh",
+            "-c",
+            "file=$(mktemp) && printf '%s' \"$1\" > \"$file\" && echo \"$file\"",
+            "sh",
+            kdl,
+        ],
+        BTreeMap::from([(TEAR_OFF_CONTEXT.to_string(), name.to_string())]),
+    );
+}
+
+/// Where the tab at `position` ends up once [`shift_tabs`] moved `positions` out of `count`
+/// tabs.
+pub fn shifted_position(position: usize, positions: &[usize], right: bool, count: usize) -> usize {
+    let mut order: Vec<usize> = (0..count).collect();
+    for from in positions {
+        let to = if right {
+            from + 1
+        } else {
+            from.wrapping_sub(1)
+        };
+        if to < count && *from < count {
+            order.swap(*from, to);
+        }
+    }
+    order
+        .iter()
+        .position(|tab| *tab == position)
+        .unwrap_or(position)
+}
+
+/// Where the tab at `position` ends up once the tab at `from` moved to `to`.
+pub fn moved_position(position: usize, from: usize, to: usize) -> usize {
+    if position == from {
+        to
+    } else if from < position && position <= to {
+        position - 1
+    } else if to <= position && position < from {
+        position + 1
+    } else {
+        position
+    }
+}
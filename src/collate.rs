@@ -0,0 +1,39 @@
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Orders names the way people read them rather than by code point: letters sort by their
+/// base letter whatever their accents (so `école` comes before `zsh`, not after), then accents
+/// break ties, then case.
+pub type CollationKey = (String, String, String);
+
+pub fn key(name: &str) -> CollationKey {
+    let lowercase = name.to_lowercase();
+    (
+        fold(&lowercase),
+        lowercase.nfd().collect(),
+        name.to_string(),
+    )
+}
+
+/// `text` without its accents, with the letters that have no decomposition spelled in plain
+/// latin letters.
+pub fn fold(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.nfd().filter(|c| !is_combining_mark(*c)) {
+        match c {
+            'đ' => folded.push('d'),
+            'Đ' => folded.push('D'),
+            'ø' => folded.push('o'),
+            'Ø' => folded.push('O'),
+            'ł' => folded.push('l'),
+            'Ł' => folded.push('L'),
+            'ß' => folded.push_str("ss"),
+            'æ' => folded.push_str("ae"),
+            'Æ' => folded.push_str("AE"),
+            'œ' => folded.push_str("oe"),
+            'Œ' => folded.push_str("OE"),
+            c => folded.push(c),
+        }
+    }
+    folded
+}
@@ -0,0 +1,48 @@
+/// The colors a tab can be labelled with.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Color {
+    /// Every color, in the order the color action goes through them.
+    pub const ALL: [Color; 6] = [
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Color::ALL.into_iter().find(|color| color.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Color::Red => "red",
+            Color::Green => "green",
+            Color::Yellow => "yellow",
+            Color::Blue => "blue",
+            Color::Magenta => "magenta",
+            Color::Cyan => "cyan",
+        }
+    }
+
+    /// The color after `current`, going back to no color after the last one.
+    pub fn next(current: Option<Color>) -> Option<Color> {
+        match current {
+            None => Some(Color::ALL[0]),
+            Some(color) => {
+                let index = Color::ALL.iter().position(|other| *other == color)?;
+                Color::ALL.get(index + 1).copied()
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+/// A `:` command typed in the filter line. Indices are 1 indexed like the list, commands
+/// without one act on the selected tab.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Rename(String),
+    Close(Option<usize>),
+    Move(usize, usize),
+    Pin(Option<usize>),
+    Protect(Option<usize>),
+}
+
+/// Every command's name and usage, in the order the hints list them.
+pub const USAGES: [(&str, &str); 5] = [
+    ("rename", ":rename <name>"),
+    ("close", ":close [index]"),
+    ("move", ":move <index> <to>"),
+    ("pin", ":pin [index]"),
+    ("protect", ":protect [index]"),
+];
+
+/// Parses what follows the `:`, the error being worth showing as is.
+pub fn parse(line: &str) -> Result<Command, String> {
+    let line = line.trim();
+    let (name, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let rest = rest.trim();
+    let mut args = rest.split_whitespace();
+
+    let index = |arg: Option<&str>| -> Result<Option<usize>, String> {
+        arg.map(|arg| {
+            arg.parse()
+                .ok()
+                .filter(|index| *index > 0)
+                .ok_or_else(|| format!("'{}' isn't a tab index", arg))
+        })
+        .transpose()
+    };
+    let usage = || {
+        let usage = USAGES
+            .iter()
+            .find(|(command, _)| *command == name)
+            .map_or("", |(_, usage)| usage);
+        format!("usage: {}", usage)
+    };
+
+    let command = match name {
+        "rename" if rest.is_empty() => return Err(usage()),
+        "rename" => Command::Rename(rest.to_string()),
+        "close" => Command::Close(index(args.next())?),
+        "move" => match (index(args.next())?, index(args.next())?) {
+            (Some(from), Some(to)) => Command::Move(from, to),
+            _ => return Err(usage()),
+        },
+        "pin" => Command::Pin(index(args.next())?),
+        "protect" => Command::Protect(index(args.next())?),
+        "" => return Err("type a command".to_string()),
+        _ => return Err(format!("no command named '{}'", name)),
+    };
+
+    if args.next().is_some() && !matches!(command, Command::Rename(_)) {
+        return Err(usage());
+    }
+
+    Ok(command)
+}
+
+/// The usages of the commands the line could still turn into, once the name is complete only
+/// its own.
+pub fn hints(line: &str) -> Vec<&'static str> {
+    let line = line.trim_start();
+    let (name, complete) = match line.split_once(' ') {
+        Some((name, _)) => (name, true),
+        None => (line, false),
+    };
+
+    USAGES
+        .iter()
+        .filter(|(command, _)| {
+            if complete {
+                *command == name
+            } else {
+                command.starts_with(name)
+            }
+        })
+        .map(|(_, usage)| *usage)
+        .collect()
+}
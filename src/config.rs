@@ -0,0 +1,369 @@
+use crate::contrast::{self, Rgb};
+use crate::keymap::Keymap;
+use crate::matcher::{self, Matcher};
+use crate::projects::Project;
+use crate::sort::Sort;
+use crate::theme::{Preset, Role};
+use crate::timestamps::TimeFormat;
+use crate::ActiveTab;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+const DEFAULT_EMPTY_MESSAGE: &str = "no tabs match '{query}'";
+const DEFAULT_ICONS: [&str; 10] = ["🚀", "🐛", "📦", "🔧", "📝", "🧪", "🌐", "🔥", "⭐", "💬"];
+
+/// How much each part of a tab's score counts, `weight_<part> = 2`. Matching alone (the
+/// default) behaves like fzf, favoring frecency like a most recently used switcher.
+pub struct Weights {
+    /// The matcher's score, with the bonuses for the index, the name and the directory.
+    pub matched: i64,
+    /// How often and how recently the tab got used.
+    pub frecency: i64,
+    /// How close the tab is to the active one.
+    pub proximity: i64,
+}
+
+/// Every option of the plugin, read once when it loads.
+pub struct Config {
+    pub empty_message: String,
+    pub matcher: Box<dyn Matcher>,
+    pub min_score: i64,
+    pub active_tab: ActiveTab,
+    pub strict_index: bool,
+    pub auto_select_single: bool,
+    pub keymap: Keymap,
+    /// Tabs whose names match one of these are protected without being marked.
+    pub protected_patterns: Vec<Regex>,
+    /// Tabs whose names match one of these aren't listed, unless revealed for a while.
+    pub ignore_patterns: Vec<Regex>,
+    pub publish_to: Option<String>,
+    pub confirm_toast_ms: u64,
+    /// The order the list starts in, it can be cycled from there.
+    pub sort: Sort,
+    pub remember_filter: bool,
+    pub show_swap_layout: bool,
+    pub show_pane_title: bool,
+    /// Closing a single tab asks first, protected tabs always do.
+    pub confirm_close: bool,
+    pub show_git_branch: bool,
+    pub on_select_command: Option<String>,
+    /// Moving tabs goes through zellij's CLI, which needs the RunCommands permission.
+    pub enable_move: bool,
+    pub show_session_name: bool,
+    pub show_mode: bool,
+    /// Answer `room:query` pipes, which needs permissions of its own.
+    pub serve_queries: bool,
+    /// Plain lines for screen readers, see [`crate::State::render_plain`].
+    pub a11y: bool,
+    /// A box around everything, for panes zellij draws no frame around.
+    pub border: bool,
+    /// `layout = "centered"`, for large floating panes.
+    pub centered: bool,
+    /// Tab and Shift+Tab move the selection like in most pickers.
+    pub tab_cycles: bool,
+    /// Esc clears the filter before it closes, `esc_behavior = "clear_then_close"`.
+    pub esc_clears: bool,
+    /// Close room after this many seconds without a key or a click.
+    pub auto_close_secs: Option<f64>,
+    /// Save the filter, sort, mode and selection as they change and start from them.
+    pub restore_state: bool,
+    /// What the icon picker offers, `icons = "🚀,🐛"` (nerd font glyphs work too).
+    pub icons: Vec<String>,
+    /// Rank the tabs working near where room was opened from first.
+    pub boost_cwd: bool,
+    /// From the `project_<name>` options, by name.
+    pub projects: Vec<Project>,
+    /// Match names ignoring their accents, so ASCII queries find names typed in Vietnamese,
+    /// French...
+    pub fold_accents: bool,
+    pub weights: Weights,
+    /// How many of the last visited tabs are listed first while the filter is empty.
+    pub recent_tabs: usize,
+    /// Mark the tabs with a pane whose command exited with an error.
+    pub show_exit_status: bool,
+    /// Pane mode lists plugin panes (the tab bar, room itself...) from the start.
+    pub show_plugin_panes: bool,
+    /// Leave fullscreen when switching to a tab with a zoomed pane.
+    pub unzoom_on_switch: bool,
+    /// How the time each tab was last focused is shown, on the rows and in the stats.
+    pub time_format: TimeFormat,
+    pub theme: Preset,
+    /// Pulse the selection after it jumps by a page or to an end of the list, for the eye to
+    /// find it again.
+    pub animate_selection: bool,
+    /// `density = "detailed"` draws every tab on two lines, the second one with its directory,
+    /// panes and command.
+    pub detailed: bool,
+    /// Panes merged into the current tab float over it, `merge_panes = "floating"`.
+    pub merge_floating: bool,
+    /// Write what room receives and does to `/data/room.log`, for bug reports.
+    pub log: bool,
+    /// The `<role>_color` options, drawing a role in something else than the preset's color.
+    pub colors: Vec<(Role, Rgb)>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::parse(&BTreeMap::new()).0
+    }
+}
+
+impl Config {
+    /// Reads `configuration`, along with what's wrong with it. An option set to something room
+    /// can't use keeps its default, one typo shouldn't take the whole plugin down.
+    pub fn parse(configuration: &BTreeMap<String, String>) -> (Self, Vec<String>) {
+        let mut reader = Reader {
+            configuration,
+            errors: Vec::new(),
+        };
+
+        // matching always ignores case, the option is only read to report a bad value
+        reader.flag("ignore_case", true);
+
+        let config = Config {
+            empty_message: reader
+                .string("empty_message")
+                .unwrap_or_else(|| DEFAULT_EMPTY_MESSAGE.to_string()),
+            matcher: reader.choice(
+                "matcher",
+                "skim, substring or prefix",
+                matcher::from_name,
+                Box::default,
+            ),
+            min_score: reader.number("min_score", 0),
+            active_tab: reader.choice(
+                "active_tab",
+                "keep, bottom or hide",
+                |value| match value {
+                    "keep" => Some(ActiveTab::Keep),
+                    "bottom" => Some(ActiveTab::Bottom),
+                    "hide" => Some(ActiveTab::Hide),
+                    _ => None,
+                },
+                ActiveTab::default,
+            ),
+            strict_index: reader.flag("strict_index", true),
+            auto_select_single: reader.flag("auto_select_single", false),
+            keymap: Keymap::from_config(&mut reader),
+            protected_patterns: reader.patterns("protected"),
+            ignore_patterns: reader.patterns("ignore"),
+            publish_to: reader
+                .string("publish_to")
+                .map(|value| value.trim().to_string()),
+            confirm_toast_ms: reader.number("confirm_toast_ms", 0),
+            sort: reader.choice(
+                "sort",
+                "score, name, panes, oldest or newest",
+                Sort::from_name,
+                Sort::default,
+            ),
+            remember_filter: reader.flag("remember_filter", false),
+            show_swap_layout: reader.flag("show_swap_layout", false),
+            show_pane_title: reader.flag("show_pane_title", false),
+            confirm_close: reader.flag("confirm_close", false),
+            show_git_branch: reader.flag("show_git_branch", false),
+            on_select_command: reader
+                .string("on_select_command")
+                .map(|command| command.trim().to_string())
+                .filter(|command| !command.is_empty()),
+            enable_move: reader.flag("enable_move", false),
+            show_session_name: reader.flag("show_session_name", false),
+            show_mode: reader.flag("show_mode", true),
+            serve_queries: reader.flag("serve_queries", false),
+            a11y: reader.flag("a11y", false),
+            border: reader.flag("border", false),
+            centered: reader.choice(
+                "layout",
+                "default or centered",
+                |value| match value {
+                    "default" => Some(false),
+                    "centered" => Some(true),
+                    _ => None,
+                },
+                || false,
+            ),
+            tab_cycles: reader.flag("tab_cycles", false),
+            esc_clears: reader.choice(
+                "esc_behavior",
+                "clear_then_close or close",
+                |value| match value {
+                    "clear_then_close" => Some(true),
+                    "close" => Some(false),
+                    _ => None,
+                },
+                || true,
+            ),
+            auto_close_secs: Some(reader.parsed("auto_close_secs", "a number of seconds", 0.0))
+                .filter(|secs| *secs > 0.0),
+            restore_state: reader.flag("restore_state", false),
+            icons: reader
+                .string("icons")
+                .map(|icons| {
+                    icons
+                        .split(',')
+                        .map(|icon| icon.trim().to_string())
+                        .filter(|icon| !icon.is_empty())
+                        .collect()
+                })
+                .unwrap_or_else(|| DEFAULT_ICONS.iter().map(|icon| icon.to_string()).collect()),
+            boost_cwd: reader.flag("boost_cwd", false),
+            projects: reader.projects(),
+            fold_accents: reader.flag("fold_accents", true),
+            recent_tabs: reader.number("recent_tabs", 0),
+            show_exit_status: reader.flag("show_exit_status", true),
+            show_plugin_panes: reader.flag("show_plugin_panes", false),
+            unzoom_on_switch: reader.flag("unzoom_on_switch", false),
+            time_format: reader.choice(
+                "time_format",
+                "relative, absolute or hidden",
+                TimeFormat::from_name,
+                TimeFormat::default,
+            ),
+            theme: reader.choice(
+                "theme",
+                "zellij, gruvbox, catppuccin or nord",
+                Preset::from_name,
+                Preset::default,
+            ),
+            colors: reader.colors(),
+            animate_selection: reader.flag("animate_selection", false),
+            log: reader.flag("log", false),
+            merge_floating: reader.choice(
+                "merge_panes",
+                "tiled or floating",
+                |value| match value {
+                    "tiled" => Some(false),
+                    "floating" => Some(true),
+                    _ => None,
+                },
+                || false,
+            ),
+            detailed: reader.choice(
+                "density",
+                "compact or detailed",
+                |value| match value {
+                    "compact" => Some(false),
+                    "detailed" => Some(true),
+                    _ => None,
+                },
+                || false,
+            ),
+            weights: Weights {
+                matched: reader.number("weight_match", 1),
+                frecency: reader.number("weight_frecency", 0),
+                proximity: reader.number("weight_proximity", 0),
+            },
+        };
+
+        (config, reader.errors)
+    }
+}
+
+/// Looks options up, noting every one that's set to something unusable.
+pub struct Reader<'a> {
+    configuration: &'a BTreeMap<String, String>,
+    errors: Vec<String>,
+}
+
+impl Reader<'_> {
+    pub fn string(&self, name: &str) -> Option<String> {
+        self.configuration.get(name).cloned()
+    }
+
+    pub fn flag(&mut self, name: &str, default: bool) -> bool {
+        self.parsed(name, "true or false", default)
+    }
+
+    pub fn number<T: FromStr>(&mut self, name: &str, default: T) -> T {
+        self.parsed(name, "a whole number", default)
+    }
+
+    /// An option parsed with `FromStr`, `expected` says what it takes when it can't be.
+    pub fn parsed<T: FromStr>(&mut self, name: &str, expected: &str, default: T) -> T {
+        self.choice(name, expected, |value| value.parse().ok(), || default)
+    }
+
+    /// An option `parse` makes sense of, `expected` says what it takes when it can't.
+    pub fn choice<T>(
+        &mut self,
+        name: &str,
+        expected: &str,
+        parse: impl Fn(&str) -> Option<T>,
+        default: impl FnOnce() -> T,
+    ) -> T {
+        let Some(value) = self.configuration.get(name) else {
+            return default();
+        };
+
+        parse(value.trim()).unwrap_or_else(|| {
+            self.errors.push(format!(
+                "{}: expected {}, got \"{}\"",
+                name, expected, value
+            ));
+            default()
+        })
+    }
+
+    /// Every `project_<name>` option, the invalid ones are left out.
+    fn projects(&mut self) -> Vec<Project> {
+        let projects: Vec<_> = self
+            .configuration
+            .iter()
+            .filter_map(|(key, value)| Some((key.strip_prefix("project_")?, value)))
+            .map(|(name, value)| (name, Project::parse(name, value)))
+            .collect();
+
+        projects
+            .into_iter()
+            .filter_map(|(name, project)| match project {
+                Ok(project) => Some(project),
+                Err(error) => {
+                    self.errors.push(format!("project_{}: {}", name, error));
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Every `<role>_color` option that's set to a color.
+    fn colors(&mut self) -> Vec<(Role, Rgb)> {
+        Role::ALL
+            .into_iter()
+            .filter_map(|role| {
+                let name = format!("{}_color", role.name());
+                let rgb = self.choice(
+                    &name,
+                    "a color like #2e3440",
+                    |value| contrast::parse_hex(value).map(Some),
+                    || None,
+                );
+                Some((role, rgb?))
+            })
+            .collect()
+    }
+
+    /// Comma separated regular expressions, the invalid ones are left out. So are empty ones, a
+    /// trailing comma would otherwise match every tab.
+    fn patterns(&mut self, name: &str) -> Vec<Regex> {
+        let Some(patterns) = self.configuration.get(name) else {
+            return Vec::new();
+        };
+
+        patterns
+            .split(',')
+            .filter(|pattern| !pattern.trim().is_empty())
+            .filter_map(|pattern| match Regex::new(pattern.trim()) {
+                Ok(regex) => Some(regex),
+                Err(_) => {
+                    self.errors.push(format!(
+                        "{}: \"{}\" isn't a valid regular expression",
+                        name,
+                        pattern.trim()
+                    ));
+                    None
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,34 @@
+use zellij_tile::prelude::*;
+
+/// Something destructive waiting for the user's go-ahead.
+pub enum Destructive {
+    /// Close every tab at these positions.
+    CloseTabs(Vec<usize>),
+}
+
+/// A yes/no question shown in place of the prompt, above the list, before running `action`.
+pub struct Confirm {
+    pub question: String,
+    pub action: Destructive,
+}
+
+impl Confirm {
+    pub fn new(question: impl Into<String>, action: Destructive) -> Self {
+        Confirm {
+            question: question.into(),
+            action,
+        }
+    }
+}
+
+/// `Some(true)` for yes, `Some(false)` for no, `None` for keys that don't answer the question.
+pub fn answer(key: Key) -> Option<bool> {
+    match key {
+        Key::Char('y' | 'Y') => Some(true),
+        Key::Char('n' | 'N') | Key::Esc => Some(false),
+        _ => None,
+    }
+}
+
+/// The hint appended to every question.
+pub const HINT: &str = "(y/n)";
@@ -0,0 +1,74 @@
+use zellij_tile::prelude::PaletteColor;
+
+pub type Rgb = (u8, u8, u8);
+
+/// The 16 ANSI colors as xterm draws them.
+const ANSI: [Rgb; 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// The color a theme's palette entry stands for, 256 color ones included.
+pub fn rgb(color: PaletteColor) -> Rgb {
+    match color {
+        PaletteColor::Rgb(rgb) => rgb,
+        PaletteColor::EightBit(index @ 0..=15) => ANSI[usize::from(index)],
+        // the 6x6x6 cube, then the grays
+        PaletteColor::EightBit(index @ 16..=231) => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+            (level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        PaletteColor::EightBit(index) => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// `#rrggbb`, the way themes write colors.
+pub fn parse_hex(hex: &str) -> Option<Rgb> {
+    let hex = hex.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |range| u8::from_str_radix(hex.get(range)?, 16).ok();
+    Some((channel(0..2)?, channel(2..4)?, channel(4..6)?))
+}
+
+/// Black or white, whichever reads better on `background`.
+pub fn readable_on(background: Rgb) -> Rgb {
+    // where black and white on a background contrast just as much (WCAG's ratios meet)
+    if luminance(background) > 0.179 {
+        (0, 0, 0)
+    } else {
+        (255, 255, 255)
+    }
+}
+
+/// WCAG's relative luminance, from 0 for black to 1 for white.
+fn luminance((red, green, blue): Rgb) -> f64 {
+    let linear = |channel: u8| {
+        let channel = f64::from(channel) / 255.0;
+        if channel <= 0.04045 {
+            channel / 12.92
+        } else {
+            ((channel + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(red) + 0.7152 * linear(green) + 0.0722 * linear(blue)
+}
@@ -0,0 +1,103 @@
+use crate::width;
+use std::fmt::{self, Write};
+
+/// Colors and styles add a few escape codes to most lines, on top of the visible text.
+const ESCAPES_PER_LINE: usize = 24;
+
+/// A whole frame, printed at once so a slow terminal never shows half of it.
+pub struct Frame {
+    text: String,
+    lines: usize,
+    /// The line every tab was drawn on, before the border and the centering move them.
+    tab_lines: Vec<(usize, usize)>,
+}
+
+impl Frame {
+    /// Has room for `rows` lines filling `cols` columns.
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Frame {
+            text: String::with_capacity(rows * (cols + ESCAPES_PER_LINE + 1)),
+            lines: 0,
+            tab_lines: Vec::new(),
+        }
+    }
+
+    /// Lets `writeln!(frame, ...)` take the place of `println!`, without a result to handle
+    /// since writing to a `String` can't fail.
+    pub fn write_fmt(&mut self, args: fmt::Arguments) {
+        let start = self.text.len();
+        let _ = self.text.write_fmt(args);
+        self.lines += self.text[start..].matches('\n').count();
+    }
+
+    /// Notes that the next line shows the tab at `position`, for clicks to find it.
+    pub fn mark_tab(&mut self, position: usize) {
+        self.tab_lines.push((self.lines, position));
+    }
+
+    /// The tab (by position) drawn on every marked line.
+    pub fn tab_lines(&self) -> Vec<(usize, usize)> {
+        self.tab_lines.clone()
+    }
+
+    /// Boxes the lines drawn so far, `width` columns wide, with `title` on the top edge. The box
+    /// is at least `min_lines` lines tall inside, so it can span the whole pane.
+    pub fn border(&mut self, title: &str, width: usize, min_lines: usize) {
+        let title = width::truncate(&format!(" {} ", title), width.saturating_sub(1));
+        let top_rule = width.saturating_sub(width::width(&title) + 1);
+
+        let mut boxed = String::with_capacity(self.text.len() + (min_lines + 2) * (width + 8));
+        boxed.push_str(&format!("┌─{}{}┐\n", title, "─".repeat(top_rule)));
+        let lines: Vec<&str> = self.text.lines().collect();
+        for index in 0..lines.len().max(min_lines) {
+            let line = lines.get(index).copied().unwrap_or_default();
+            let padding = width.saturating_sub(width::visible_width(line));
+            boxed.push_str(&format!("│{}{}│\n", line, " ".repeat(padding)));
+        }
+        boxed.push_str(&format!("└{}┘\n", "─".repeat(width)));
+
+        self.text = boxed;
+    }
+
+    /// Moves the lines drawn so far, `width` columns wide, to the middle of a pane of `rows` by
+    /// `cols`. Returns how many lines they were pushed down by.
+    pub fn center(&mut self, rows: usize, cols: usize, width: usize) -> usize {
+        let top = rows.saturating_sub(self.text.lines().count()) / 2;
+        let left = " ".repeat(cols.saturating_sub(width) / 2);
+
+        let mut centered = String::with_capacity(self.text.len() + top + rows * left.len());
+        centered.push_str(&"\n".repeat(top));
+        for line in self.text.lines() {
+            centered.push_str(&left);
+            centered.push_str(line);
+            centered.push('\n');
+        }
+
+        self.text = centered;
+        top
+    }
+
+    /// Makes the frame `lines` lines long, dropping the ones past them or adding empty ones.
+    pub fn fit_lines(&mut self, lines: usize) {
+        if self.lines > lines {
+            let end = match lines.checked_sub(1) {
+                Some(last) => self
+                    .text
+                    .match_indices('\n')
+                    .nth(last)
+                    .map_or(self.text.len(), |(index, _)| index + 1),
+                None => 0,
+            };
+            self.text.truncate(end);
+            self.tab_lines.retain(|(line, _)| *line < lines);
+        } else {
+            self.text.push_str(&"\n".repeat(lines - self.lines));
+        }
+        self.lines = lines;
+    }
+
+    /// Everything drawn, ready to be printed.
+    pub fn into_text(self) -> String {
+        self.text
+    }
+}
@@ -0,0 +1,26 @@
+use crate::host;
+use crate::log;
+use std::collections::BTreeMap;
+use zellij_tile::prelude::*;
+
+/// Marks the runs of `on_select_command`, to tell their results apart from other commands'.
+pub const CONTEXT: &str = "on_select_command";
+
+/// Runs the user's `on_select_command` through `sh` for the tab room switched to, `{name}` and
+/// `{index}` (1 indexed, like the list) being replaced by the tab's.
+pub fn run_on_select(command: &str, tab: &TabInfo) {
+    let command = command
+        .replace("{name}", &shell_quote(&tab.name))
+        .replace("{index}", &(tab.position + 1).to_string());
+
+    log::action(&format!("run on_select_command={:?}", command));
+    host::run_command(
+        &["sh", "-c", &command],
+        BTreeMap::from([(CONTEXT.to_string(), String::new())]),
+    );
+}
+
+/// Tab names are typed by anyone, keep them a single word whatever they contain.
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
@@ -0,0 +1,350 @@
+//! Everything room asks of zellij. In the plugin these are zellij_tile's own functions, built for
+//! anything else (the tests) they note the commands down instead of sending them to a host that
+//! isn't there, for [`take_commands`] to hand over.
+//!
+//! Room's files in `/data` are read and written through here as well. In the plugin that's the
+//! folder zellij keeps for it, elsewhere the files are kept in memory, one set per thread, so a
+//! test starts with none of them unless it [`write`]s them first.
+
+#[cfg(target_arch = "wasm32")]
+pub use zellij_tile::prelude::{
+    clear_screen, cli_pipe_output, close_focus, close_focused_tab, detach, dump_session_layout,
+    edit_scrollback, focus_or_create_tab, focus_plugin_pane, focus_terminal_pane, get_plugin_ids,
+    go_to_tab_name, new_tab, new_tabs_with_layout, new_tabs_with_layout_info, next_swap_layout,
+    open_command_pane, open_command_pane_floating, open_terminal, open_terminal_floating,
+    pipe_message_to_plugin, post_message_to, post_message_to_plugin, previous_swap_layout,
+    rename_tab, request_permission, run_command, run_command_with_env_variables_and_cwd,
+    set_timeout, subscribe, switch_session_with_focus, switch_session_with_layout, switch_tab_to,
+    toggle_active_tab_sync, toggle_focus_fullscreen, toggle_pane_embed_or_eject,
+    toggle_pane_frames, unblock_cli_pipe_input, unsubscribe,
+};
+
+#[cfg(target_arch = "wasm32")]
+pub use stored::*;
+
+#[cfg(target_arch = "wasm32")]
+mod stored {
+    use std::fs::{self, OpenOptions};
+    use std::io::{self, Write};
+    use std::path::Path;
+
+    pub use std::fs::{read_to_string, rename, write};
+
+    pub fn exists<P: AsRef<Path>>(path: P) -> bool {
+        fs::metadata(path).is_ok()
+    }
+
+    /// The file's size in bytes, 0 when there's none.
+    pub fn size<P: AsRef<Path>>(path: P) -> u64 {
+        fs::metadata(path).map_or(0, |metadata| metadata.len())
+    }
+
+    /// Adds `contents` to the end of the file, creating it if need be.
+    pub fn append<P: AsRef<Path>>(path: P, contents: &str) -> io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(contents.as_bytes())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use recorded::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod recorded {
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use zellij_tile::prelude::*;
+
+    thread_local! {
+        static COMMANDS: RefCell<Vec<PluginCommand>> = const { RefCell::new(Vec::new()) };
+        static FILES: RefCell<BTreeMap<PathBuf, String>> = const { RefCell::new(BTreeMap::new()) };
+    }
+
+    /// The commands issued since the last call, oldest first.
+    pub fn take_commands() -> Vec<PluginCommand> {
+        COMMANDS.with(|commands| std::mem::take(&mut *commands.borrow_mut()))
+    }
+
+    fn record(command: PluginCommand) {
+        COMMANDS.with(|commands| commands.borrow_mut().push(command));
+    }
+
+    /// Forgets every file written so far.
+    pub fn remove_files() {
+        FILES.with(|files| files.borrow_mut().clear());
+    }
+
+    pub fn read_to_string<P: AsRef<Path>>(path: P) -> io::Result<String> {
+        FILES.with(|files| {
+            files
+                .borrow()
+                .get(path.as_ref())
+                .cloned()
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+        })
+    }
+
+    pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+        let contents = String::from_utf8_lossy(contents.as_ref()).into_owned();
+        FILES.with(|files| {
+            files
+                .borrow_mut()
+                .insert(path.as_ref().to_path_buf(), contents)
+        });
+        Ok(())
+    }
+
+    pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()> {
+        FILES.with(|files| {
+            let mut files = files.borrow_mut();
+            let contents = files
+                .remove(from.as_ref())
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+            files.insert(to.as_ref().to_path_buf(), contents);
+            Ok(())
+        })
+    }
+
+    pub fn exists<P: AsRef<Path>>(path: P) -> bool {
+        FILES.with(|files| files.borrow().contains_key(path.as_ref()))
+    }
+
+    pub fn size<P: AsRef<Path>>(path: P) -> u64 {
+        FILES.with(|files| {
+            files
+                .borrow()
+                .get(path.as_ref())
+                .map_or(0, |contents| contents.len() as u64)
+        })
+    }
+
+    pub fn append<P: AsRef<Path>>(path: P, contents: &str) -> io::Result<()> {
+        FILES.with(|files| {
+            files
+                .borrow_mut()
+                .entry(path.as_ref().to_path_buf())
+                .or_default()
+                .push_str(contents)
+        });
+        Ok(())
+    }
+
+    pub fn clear_screen() {
+        record(PluginCommand::ClearScreen);
+    }
+
+    pub fn cli_pipe_output(pipe_name: &str, output: &str) {
+        record(PluginCommand::CliPipeOutput(
+            pipe_name.to_owned(),
+            output.to_owned(),
+        ));
+    }
+
+    pub fn close_focus() {
+        record(PluginCommand::CloseFocus);
+    }
+
+    pub fn close_focused_tab() {
+        record(PluginCommand::CloseFocusedTab);
+    }
+
+    pub fn detach() {
+        record(PluginCommand::Detach);
+    }
+
+    pub fn dump_session_layout() {
+        record(PluginCommand::DumpSessionLayout);
+    }
+
+    pub fn edit_scrollback() {
+        record(PluginCommand::EditScrollback);
+    }
+
+    pub fn focus_or_create_tab(tab_name: &str) {
+        record(PluginCommand::FocusOrCreateTab(tab_name.to_owned()));
+    }
+
+    pub fn focus_plugin_pane(plugin_pane_id: u32, should_float_if_hidden: bool) {
+        record(PluginCommand::FocusPluginPane(
+            plugin_pane_id,
+            should_float_if_hidden,
+        ));
+    }
+
+    pub fn focus_terminal_pane(terminal_pane_id: u32, should_float_if_hidden: bool) {
+        record(PluginCommand::FocusTerminalPane(
+            terminal_pane_id,
+            should_float_if_hidden,
+        ));
+    }
+
+    /// Ids of a plugin zellij never started, opened from `/`.
+    pub fn get_plugin_ids() -> PluginIds {
+        record(PluginCommand::GetPluginIds);
+        PluginIds {
+            plugin_id: 0,
+            zellij_pid: 0,
+            initial_cwd: PathBuf::from("/"),
+        }
+    }
+
+    pub fn go_to_tab_name(tab_name: &str) {
+        record(PluginCommand::GoToTabName(tab_name.to_owned()));
+    }
+
+    pub fn new_tab() {
+        record(PluginCommand::NewTab);
+    }
+
+    pub fn new_tabs_with_layout(layout: &str) {
+        record(PluginCommand::NewTabsWithLayout(layout.to_owned()));
+    }
+
+    pub fn new_tabs_with_layout_info(layout_info: LayoutInfo) {
+        record(PluginCommand::NewTabsWithLayoutInfo(layout_info));
+    }
+
+    pub fn next_swap_layout() {
+        record(PluginCommand::NextSwapLayout);
+    }
+
+    pub fn open_command_pane(command_to_run: CommandToRun) {
+        record(PluginCommand::OpenCommandPane(command_to_run));
+    }
+
+    pub fn open_command_pane_floating(
+        command_to_run: CommandToRun,
+        coordinates: Option<FloatingPaneCoordinates>,
+    ) {
+        record(PluginCommand::OpenCommandPaneFloating(
+            command_to_run,
+            coordinates,
+        ));
+    }
+
+    pub fn open_terminal<P: AsRef<Path>>(path: P) {
+        let file_to_open = FileToOpen::new(path.as_ref());
+        record(PluginCommand::OpenTerminal(file_to_open));
+    }
+
+    pub fn open_terminal_floating<P: AsRef<Path>>(
+        path: P,
+        coordinates: Option<FloatingPaneCoordinates>,
+    ) {
+        let file_to_open = FileToOpen::new(path.as_ref());
+        record(PluginCommand::OpenTerminalFloating(
+            file_to_open,
+            coordinates,
+        ));
+    }
+
+    pub fn pipe_message_to_plugin(message_to_plugin: MessageToPlugin) {
+        record(PluginCommand::MessageToPlugin(message_to_plugin));
+    }
+
+    pub fn post_message_to(plugin_message: PluginMessage) {
+        record(PluginCommand::PostMessageTo(plugin_message));
+    }
+
+    pub fn post_message_to_plugin(plugin_message: PluginMessage) {
+        record(PluginCommand::PostMessageToPlugin(plugin_message));
+    }
+
+    pub fn previous_swap_layout() {
+        record(PluginCommand::PreviousSwapLayout);
+    }
+
+    pub fn rename_tab<S: ToString>(tab_position: u32, new_name: S) {
+        record(PluginCommand::RenameTab(tab_position, new_name.to_string()));
+    }
+
+    pub fn request_permission(permissions: &[PermissionType]) {
+        record(PluginCommand::RequestPluginPermissions(permissions.into()));
+    }
+
+    pub fn run_command(cmd: &[&str], context: BTreeMap<String, String>) {
+        run_command_with_env_variables_and_cwd(cmd, BTreeMap::new(), PathBuf::from("."), context);
+    }
+
+    pub fn run_command_with_env_variables_and_cwd(
+        cmd: &[&str],
+        env_variables: BTreeMap<String, String>,
+        cwd: PathBuf,
+        context: BTreeMap<String, String>,
+    ) {
+        record(PluginCommand::RunCommand(
+            cmd.iter().map(|part| part.to_string()).collect(),
+            env_variables,
+            cwd,
+            context,
+        ));
+    }
+
+    pub fn set_timeout(secs: f64) {
+        record(PluginCommand::SetTimeout(secs));
+    }
+
+    pub fn subscribe(event_types: &[EventType]) {
+        record(PluginCommand::Subscribe(
+            event_types.iter().cloned().collect(),
+        ));
+    }
+
+    pub fn switch_session_with_focus(
+        name: &str,
+        tab_position: Option<usize>,
+        pane_id: Option<(u32, bool)>,
+    ) {
+        record(PluginCommand::SwitchSession(ConnectToSession {
+            name: Some(name.to_owned()),
+            tab_position,
+            pane_id,
+            ..Default::default()
+        }));
+    }
+
+    pub fn switch_session_with_layout(
+        name: Option<&str>,
+        layout: LayoutInfo,
+        cwd: Option<PathBuf>,
+    ) {
+        record(PluginCommand::SwitchSession(ConnectToSession {
+            name: name.map(|name| name.to_string()),
+            layout: Some(layout),
+            cwd,
+            ..Default::default()
+        }));
+    }
+
+    pub fn switch_tab_to(tab_idx: u32) {
+        record(PluginCommand::SwitchTabTo(tab_idx));
+    }
+
+    pub fn toggle_active_tab_sync() {
+        record(PluginCommand::ToggleActiveTabSync);
+    }
+
+    pub fn toggle_focus_fullscreen() {
+        record(PluginCommand::ToggleFocusFullscreen);
+    }
+
+    pub fn toggle_pane_embed_or_eject() {
+        record(PluginCommand::TogglePaneEmbedOrEject);
+    }
+
+    pub fn toggle_pane_frames() {
+        record(PluginCommand::TogglePaneFrames);
+    }
+
+    pub fn unblock_cli_pipe_input(pipe_name: &str) {
+        record(PluginCommand::UnblockCliPipeInput(pipe_name.to_owned()));
+    }
+
+    pub fn unsubscribe(event_types: &[EventType]) {
+        record(PluginCommand::Unsubscribe(
+            event_types.iter().cloned().collect(),
+        ));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::host;
+use crate::worker;
+
+const PATH: &str = "/data/jumplist";
+/// Keeps the file small, older visits are dropped first.
+const MAX_ENTRIES: usize = 100;
+
+/// The tabs visited through room, by name, walked like vim's jumplist. Every plugin instance is
+/// short lived so the list round-trips through `/data`.
+#[derive(Default)]
+pub struct JumpList {
+    entries: Vec<String>,
+    cursor: usize,
+}
+
+impl JumpList {
+    /// Reads the list saved by a previous instance, or starts an empty one.
+    pub fn load() -> Self {
+        let Ok(contents) = host::read_to_string(PATH) else {
+            return Self::default();
+        };
+
+        let mut lines = contents.lines();
+        let cursor = lines.next().and_then(|line| line.parse().ok()).unwrap_or(0);
+        let entries: Vec<String> = lines.map(String::from).collect();
+
+        JumpList {
+            cursor: cursor.min(entries.len().saturating_sub(1)),
+            entries,
+        }
+    }
+
+    pub fn save(&self) {
+        let mut contents = self.cursor.to_string();
+        for entry in &self.entries {
+            contents.push('\n');
+            contents.push_str(entry);
+        }
+
+        worker::write(PATH, &contents);
+    }
+
+    /// Records a switch from `from` to `to`, dropping anything after the cursor like vim does.
+    pub fn record(&mut self, from: Option<&str>, to: &str) {
+        self.entries.truncate(self.cursor + 1);
+        if let Some(from) = from {
+            self.push(from);
+        }
+        self.push(to);
+    }
+
+    /// Steps back from `current`, skipping entries for which `exists` is false (closed or
+    /// renamed tabs), and returns the name of the tab to switch to.
+    pub fn back(&mut self, current: Option<&str>, exists: impl Fn(&str) -> bool) -> Option<&str> {
+        // we're somewhere the list doesn't know about (switched without room), remember it so
+        // a forward jump can come back here
+        if let Some(current) = current {
+            if self.entries.get(self.cursor).map(String::as_str) != Some(current) {
+                self.record(None, current);
+            }
+        }
+
+        let target = (0..self.cursor)
+            .rev()
+            .find(|index| exists(&self.entries[*index]))?;
+        self.cursor = target;
+        Some(&self.entries[target])
+    }
+
+    /// Steps forward, skipping entries for which `exists` is false, and returns the name of the
+    /// tab to switch to.
+    pub fn forward(&mut self, exists: impl Fn(&str) -> bool) -> Option<&str> {
+        let target =
+            (self.cursor + 1..self.entries.len()).find(|index| exists(&self.entries[*index]))?;
+        self.cursor = target;
+        Some(&self.entries[target])
+    }
+
+    /// Every tab visited through room once, the most recent first.
+    pub fn recent(&self) -> Vec<&str> {
+        let mut recent: Vec<&str> = Vec::new();
+        for entry in self.entries.iter().rev() {
+            if !recent.contains(&entry.as_str()) {
+                recent.push(entry);
+            }
+        }
+        recent
+    }
+
+    fn push(&mut self, name: &str) {
+        if self.entries.last().map(String::as_str) != Some(name) {
+            self.entries.push(name.to_string());
+        }
+
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.drain(..self.entries.len() - MAX_ENTRIES);
+        }
+        self.cursor = self.entries.len() - 1;
+    }
+}
@@ -0,0 +1,228 @@
+use crate::config::Reader;
+use zellij_tile::prelude::*;
+
+/// The configurable key bindings, each one read from a `<action>_key` option written the way
+/// zellij writes keys in its own config (e.g. `jump_back_key = "Ctrl o"`).
+pub struct Keymap {
+    pub jump_back: Key,
+    pub jump_forward: Key,
+    pub switch_keep_open: Key,
+    /// Shows the next matching tab without closing, to walk through the matches.
+    pub walk_next: Key,
+    pub walk_previous: Key,
+    /// Switches to the tab the stats say is used the most, the current one aside.
+    pub most_used: Key,
+    /// Copies the selected tab's name to the clipboard.
+    pub copy_name: Key,
+    /// Copies it as `index:name`.
+    pub copy_index: Key,
+    /// Lists the tabs the `ignore` patterns hide, or hides them again.
+    pub reveal_ignored: Key,
+    /// Draws every tab on two lines with its details, or back on one.
+    pub density: Key,
+    /// Marks the selected tab, or unmarks it.
+    pub mark: Key,
+    /// Moves the marked tabs (or the selected one) one step left, with `enable_move`.
+    pub move_up: Key,
+    pub move_down: Key,
+    pub actions: Key,
+    pub cycle_sort: Key,
+    pub clear_filter: Key,
+    /// Clears the filter too, on the key shells clear the screen with.
+    pub clear_all: Key,
+    /// Terminals don't report Shift together with Ctrl, so Ctrl+Shift+x arrives as Ctrl+x.
+    pub close_matched: Key,
+    pub repeat: Key,
+    /// Selects the next tab with the same name as the selected one.
+    pub next_duplicate: Key,
+    pub global: Key,
+    pub close: Key,
+    pub palette: Key,
+    pub cheatsheet: Key,
+    /// From the cheatsheet, shows a keybinding launching room as configured.
+    pub snippet: Key,
+    pub stats: Key,
+    pub permissions: Key,
+    pub panes: Key,
+    /// Lists the tabs with their panes as branches to expand.
+    pub tree: Key,
+    /// Lists plugin panes in pane mode too, or hides them again.
+    pub plugin_panes: Key,
+    /// Cycles pane mode through every pane, the floating ones only and the tiled ones only.
+    pub pane_kind: Key,
+    pub projects: Key,
+    pub cycle_mode: Key,
+    /// Along with Down and Ctrl+n, which some zellij setups bind. Not Ctrl+j by default, zellij
+    /// hands it to plugins as Enter unless it binds Ctrl+j itself.
+    pub select_down: Key,
+    /// Along with Up and Ctrl+p.
+    pub select_up: Key,
+    /// Moves the selection by as many tabs as the list shows.
+    pub page_down: Key,
+    pub page_up: Key,
+    pub select_first: Key,
+    pub select_last: Key,
+    /// Keys that switch to the selected tab, `confirm_keys = "Enter, Y"` by default.
+    pub confirm: Vec<Key>,
+    /// Filters applied by a function key, `search_f1 = "backend"`.
+    pub searches: Vec<(Key, String)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap {
+            jump_back: Key::Ctrl('o'),
+            jump_forward: Key::Ctrl('i'),
+            switch_keep_open: Key::Alt(CharOrArrow::Char('\n')),
+            walk_next: Key::Alt(CharOrArrow::Char('n')),
+            walk_previous: Key::Alt(CharOrArrow::Char('p')),
+            most_used: Key::Alt(CharOrArrow::Char('u')),
+            copy_name: Key::Alt(CharOrArrow::Char('c')),
+            copy_index: Key::Alt(CharOrArrow::Char('C')),
+            mark: Key::Alt(CharOrArrow::Char('x')),
+            reveal_ignored: Key::Alt(CharOrArrow::Char('h')),
+            density: Key::Alt(CharOrArrow::Char('l')),
+            move_up: Key::Alt(CharOrArrow::Direction(Direction::Up)),
+            move_down: Key::Alt(CharOrArrow::Direction(Direction::Down)),
+            actions: Key::Ctrl('a'),
+            cycle_sort: Key::Ctrl('s'),
+            clear_filter: Key::Ctrl('u'),
+            clear_all: Key::Ctrl('l'),
+            close_matched: Key::Ctrl('x'),
+            // a plain `.` has to stay typeable in the filter
+            repeat: Key::Alt(CharOrArrow::Char('.')),
+            next_duplicate: Key::Alt(CharOrArrow::Char('d')),
+            global: Key::Ctrl('g'),
+            close: Key::Ctrl('d'),
+            palette: Key::Ctrl('e'),
+            cheatsheet: Key::Ctrl('y'),
+            snippet: Key::Alt(CharOrArrow::Char('s')),
+            stats: Key::Ctrl('t'),
+            permissions: Key::Ctrl('r'),
+            panes: Key::Ctrl('w'),
+            tree: Key::Alt(CharOrArrow::Char('t')),
+            plugin_panes: Key::Alt(CharOrArrow::Char('i')),
+            pane_kind: Key::Alt(CharOrArrow::Char('f')),
+            projects: Key::Ctrl('f'),
+            cycle_mode: Key::Alt(CharOrArrow::Char('m')),
+            select_down: Key::Ctrl('n'),
+            select_up: Key::Ctrl('k'),
+            page_down: Key::PageDown,
+            page_up: Key::PageUp,
+            select_first: Key::Home,
+            select_last: Key::End,
+            confirm: vec![Key::Char('\n'), Key::Char('Y')],
+            searches: Vec::new(),
+        }
+    }
+}
+
+impl Keymap {
+    pub fn from_config(reader: &mut Reader) -> Self {
+        let defaults = Keymap::default();
+        let mut key = |name: &str, default: Key| {
+            reader.parsed(name, "a key like \"Ctrl o\" or \"Alt m\"", default)
+        };
+
+        Keymap {
+            jump_back: key("jump_back_key", defaults.jump_back),
+            jump_forward: key("jump_forward_key", defaults.jump_forward),
+            switch_keep_open: key("switch_keep_open_key", defaults.switch_keep_open),
+            walk_next: key("walk_next_key", defaults.walk_next),
+            walk_previous: key("walk_previous_key", defaults.walk_previous),
+            most_used: key("most_used_key", defaults.most_used),
+            copy_name: key("copy_name_key", defaults.copy_name),
+            copy_index: key("copy_index_key", defaults.copy_index),
+            mark: key("mark_key", defaults.mark),
+            reveal_ignored: key("reveal_ignored_key", defaults.reveal_ignored),
+            density: key("density_key", defaults.density),
+            move_up: key("move_up_key", defaults.move_up),
+            move_down: key("move_down_key", defaults.move_down),
+            actions: key("actions_key", defaults.actions),
+            cycle_sort: key("cycle_sort_key", defaults.cycle_sort),
+            clear_filter: key("clear_filter_key", defaults.clear_filter),
+            clear_all: key("clear_all_key", defaults.clear_all),
+            close_matched: key("close_matched_key", defaults.close_matched),
+            repeat: key("repeat_key", defaults.repeat),
+            next_duplicate: key("next_duplicate_key", defaults.next_duplicate),
+            global: key("global_key", defaults.global),
+            close: key("close_key", defaults.close),
+            palette: key("palette_key", defaults.palette),
+            cheatsheet: key("cheatsheet_key", defaults.cheatsheet),
+            snippet: key("snippet_key", defaults.snippet),
+            stats: key("stats_key", defaults.stats),
+            permissions: key("permissions_key", defaults.permissions),
+            panes: key("panes_key", defaults.panes),
+            tree: key("tree_key", defaults.tree),
+            plugin_panes: key("plugin_panes_key", defaults.plugin_panes),
+            pane_kind: key("pane_kind_key", defaults.pane_kind),
+            projects: key("projects_key", defaults.projects),
+            cycle_mode: key("cycle_mode_key", defaults.cycle_mode),
+            select_down: key("select_down_key", defaults.select_down),
+            select_up: key("select_up_key", defaults.select_up),
+            page_down: key("page_down_key", defaults.page_down),
+            page_up: key("page_up_key", defaults.page_up),
+            select_first: key("select_first_key", defaults.select_first),
+            select_last: key("select_last_key", defaults.select_last),
+            confirm: reader.choice(
+                "confirm_keys",
+                "a comma separated list of keys",
+                |value| {
+                    value
+                        .split(',')
+                        .map(|key| key.trim().parse().ok())
+                        .collect()
+                },
+                || defaults.confirm,
+            ),
+            searches: (1..=12)
+                .filter_map(|n| {
+                    let search = reader.string(&format!("search_f{}", n))?;
+                    Some((Key::F(n), search))
+                })
+                .collect(),
+        }
+    }
+
+    /// Whether `pressed` moves the selection down.
+    pub fn down(&self, pressed: Key) -> bool {
+        matches!(pressed, Key::Down | Key::Ctrl('n')) || matches(self.select_down, pressed)
+    }
+
+    /// Whether `pressed` moves the selection up.
+    pub fn up(&self, pressed: Key) -> bool {
+        matches!(pressed, Key::Up | Key::Ctrl('p')) || matches(self.select_up, pressed)
+    }
+
+    /// Whether `pressed` clears the filter.
+    pub fn clears(&self, pressed: Key) -> bool {
+        matches(self.clear_filter, pressed) || matches(self.clear_all, pressed)
+    }
+
+    /// The saved search `pressed` applies, if any.
+    pub fn search(&self, pressed: Key) -> Option<&str> {
+        self.searches
+            .iter()
+            .find(|(bound, _)| *bound == pressed)
+            .map(|(_, search)| search.as_str())
+    }
+
+    /// Whether `pressed` is one of the confirm keys.
+    pub fn confirms(&self, pressed: Key) -> bool {
+        self.confirm.iter().any(|bound| matches(*bound, pressed))
+    }
+}
+
+/// Whether `pressed` triggers the binding `bound`. Terminals can't tell Ctrl+i from Tab so
+/// both arrive as a tab character, just like in vim. Enter is a carriage return on some
+/// terminals, and on most keypads.
+pub fn matches(bound: Key, pressed: Key) -> bool {
+    bound == pressed
+        || (bound == Key::Ctrl('i') && pressed == Key::Char('\t'))
+        || (bound == Key::Char('\n') && pressed == Key::Char('\r'))
+}
+
+/// Enter however the terminal sends it, for where letters are typed and can't confirm.
+pub fn is_enter(pressed: Key) -> bool {
+    matches(Key::Char('\n'), pressed)
+}
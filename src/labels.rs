@@ -0,0 +1,57 @@
+use crate::host;
+use crate::worker;
+use std::collections::BTreeMap;
+
+/// A short label per tab name saved in `/data`, like the color a tab was given.
+#[derive(Default)]
+pub struct TabLabels {
+    path: &'static str,
+    labels: BTreeMap<String, String>,
+}
+
+impl TabLabels {
+    pub fn load(path: &'static str) -> Self {
+        let labels = host::read_to_string(path)
+            .map(|contents| {
+                contents
+                    .lines()
+                    .filter_map(|line| {
+                        let (label, name) = line.split_once('\t')?;
+                        Some((name.to_string(), label.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        TabLabels { path, labels }
+    }
+
+    pub fn save(&self) {
+        let contents: Vec<String> = self
+            .labels
+            .iter()
+            .map(|(name, label)| format!("{}\t{}", label, name))
+            .collect();
+        worker::write(self.path, &contents.join("\n"));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.labels.get(name).map(String::as_str)
+    }
+
+    /// Labels `name`, or takes its label away when there's none.
+    pub fn set(&mut self, name: &str, label: Option<&str>) {
+        match label {
+            Some(label) => self.labels.insert(name.to_string(), label.to_string()),
+            None => self.labels.remove(name),
+        };
+    }
+
+    /// Follows a tab to its new name, since labels only know tabs by name.
+    pub fn rename(&mut self, old_name: &str, new_name: &str) {
+        if let Some(label) = self.labels.remove(old_name) {
+            self.labels.insert(new_name.to_string(), label);
+            self.save();
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+/// The name zellij gives the `CustomMessage` answering `dump_session_layout`.
+pub const SESSION_LAYOUT: &str = "session_layout";
+
+/// The working directory of every tab, by position, read from a dumped session layout since
+/// zellij doesn't tell plugins where panes are. A tab's directory is the one of its focused pane
+/// (or first pane), resolved against the tab's and the layout's own `cwd`.
+pub fn tab_cwds(kdl: &str) -> Vec<Option<PathBuf>> {
+    let mut layout_cwd = None;
+    let mut tabs = Vec::new();
+    let mut tab_cwd = None;
+    // (cwd, focused) of the pane picked for the current tab so far
+    let mut pane: Option<(String, bool)> = None;
+    let mut depth = 0usize;
+
+    for line in kdl.lines().map(str::trim) {
+        // the layout's own node is depth 1, its tabs open at depth 1 too, anything deeper (like
+        // the tabs of swap layouts) belongs to something else
+        if depth == 1 && line.starts_with("cwd ") {
+            layout_cwd = string_argument(line);
+        } else if depth == 1 && (line == "tab" || line.starts_with("tab ")) {
+            tab_cwd = attribute(line, "cwd");
+            pane = None;
+            tabs.push(None);
+        } else if depth > 1 && !tabs.is_empty() && line.starts_with("pane") {
+            if let Some(cwd) = attribute(line, "cwd") {
+                let focused = line.contains("focus=true");
+                if pane.as_ref().is_none_or(|(_, picked)| !picked && focused) {
+                    pane = Some((cwd, focused));
+                }
+            }
+        }
+
+        depth += line.matches('{').count();
+        depth = depth.saturating_sub(line.matches('}').count());
+
+        // the tab just ended, settle its directory
+        if depth == 1 {
+            if let Some(last) = tabs.last_mut().filter(|last| last.is_none()) {
+                let mut cwd = layout_cwd.clone().map(PathBuf::from).unwrap_or_default();
+                for part in [tab_cwd.take(), pane.take().map(|(cwd, _)| cwd)]
+                    .into_iter()
+                    .flatten()
+                {
+                    cwd.push(part);
+                }
+                *last = cwd.is_absolute().then_some(cwd);
+            }
+        }
+    }
+
+    tabs
+}
+
+/// The value of `name="..."` on a node's line.
+fn attribute(line: &str, name: &str) -> Option<String> {
+    let start = line.find(&format!("{}=\"", name))? + name.len() + 2;
+    let end = line[start..].find('"')? + start;
+    Some(line[start..end].to_string())
+}
+
+/// The first string argument of a node, `cwd "/home"`.
+fn string_argument(line: &str) -> Option<String> {
+    let start = line.find('"')? + 1;
+    let end = line[start..].find('"')? + start;
+    Some(line[start..end].to_string())
+}
@@ -0,0 +1,4200 @@
+mod app;
+mod cheatsheet;
+mod cli;
+mod clipboard;
+mod collate;
+mod color;
+mod command;
+mod config;
+mod confirm;
+mod contrast;
+mod frame;
+mod hooks;
+pub mod host;
+mod jumplist;
+mod keymap;
+mod labels;
+mod layout;
+mod log;
+mod matcher;
+mod menu;
+mod onboarding;
+mod palette;
+mod permissions;
+mod pipes;
+mod projects;
+mod query;
+mod score;
+mod search;
+mod sections;
+mod snapshot;
+mod snippet;
+mod sort;
+mod stats;
+mod tab_set;
+mod theme;
+mod timestamps;
+mod width;
+mod worker;
+
+pub use app::App;
+pub use log::Logger;
+pub use worker::Writer;
+
+use cheatsheet::Binding;
+use color::Color;
+use command::Command;
+use config::Config;
+use confirm::{Confirm, Destructive};
+use frame::Frame;
+use jumplist::JumpList;
+use labels::TabLabels;
+use menu::{Menu, TabAction};
+use owo_colors::OwoColorize;
+use palette::PaletteAction;
+use projects::Project;
+use query::{Attributes, Query};
+use search::SearchStr;
+use sections::{Kind, Line, Section};
+use snapshot::Snapshot;
+use sort::Sort;
+use stats::Stats;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+use tab_set::TabSet;
+use theme::{Role, Theme};
+use timestamps::Timestamps;
+use zellij_tile::prelude::*;
+
+/// Where `remember_filter` keeps the filter between two openings of the plugin.
+const FILTER_PATH: &str = "/data/filter";
+/// How long saved files may wait for more changes before they get written.
+const FLUSH_SECS: f64 = 1.0;
+/// How much earlier than asked a timer may seem to fire, the clock only counts milliseconds.
+const TIMER_SLACK_SECS: f64 = 0.01;
+/// How often pane updates may redraw the list, zellij sends one for every keystroke in a pane.
+const PANE_REFRESH_SECS: f64 = 0.1;
+/// Frames the selection pulses for with `animate_selection`, flashing on every odd one.
+const PULSE_STEPS: u8 = 4;
+const PULSE_STEP_SECS: f64 = 0.06;
+/// The most columns the centered layout spreads over, wider lists are hard to scan.
+const CENTERED_MAX_COLS: usize = 80;
+/// Marks our git commands, holding the directory the branch was asked for.
+const GIT_BRANCH_CONTEXT: &str = "git_branch";
+/// How long a directory's branch is trusted before git is asked again.
+const BRANCH_MAX_AGE_SECS: f64 = 30.0;
+
+/// What tabs are listed by, see [`State::ranked_tabs`].
+type RankKey = (
+    usize,
+    bool,
+    bool,
+    std::cmp::Reverse<i64>,
+    std::cmp::Reverse<i64>,
+    usize,
+);
+
+/// Keeps the first `count` of `tabs` in the order they're listed, sorting only those.
+fn keep_first(tabs: &mut Vec<(RankKey, &TabInfo)>, count: usize) {
+    if count == 0 {
+        tabs.clear();
+        return;
+    }
+    if tabs.len() > count {
+        tabs.select_nth_unstable_by_key(count - 1, |(key, _)| *key);
+        tabs.truncate(count);
+    }
+    tabs.sort_unstable_by_key(|(key, _)| *key);
+}
+
+/// Where the tab we're currently on shows up in the results.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum ActiveTab {
+    /// Ranked like every other tab, and selected when the plugin opens.
+    #[default]
+    Keep,
+    /// Always listed after the other matches.
+    Bottom,
+    /// Not listed at all.
+    Hide,
+}
+
+/// Which panes pane mode lists, cycled with a key.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum PaneKind {
+    #[default]
+    All,
+    Floating,
+    Tiled,
+}
+
+impl PaneKind {
+    fn next(self) -> Self {
+        match self {
+            PaneKind::All => PaneKind::Floating,
+            PaneKind::Floating => PaneKind::Tiled,
+            PaneKind::Tiled => PaneKind::All,
+        }
+    }
+
+    fn includes(self, pane: &PaneInfo) -> bool {
+        match self {
+            PaneKind::All => true,
+            PaneKind::Floating => pane.is_floating,
+            PaneKind::Tiled => !pane.is_floating,
+        }
+    }
+
+    /// How the listed panes are called, `"floating panes"`.
+    fn label(self) -> &'static str {
+        match self {
+            PaneKind::All => "panes",
+            PaneKind::Floating => "floating panes",
+            PaneKind::Tiled => "tiled panes",
+        }
+    }
+}
+
+/// A line of global mode, a session or one of its tabs.
+enum GlobalLine<'a> {
+    Session {
+        name: &'a str,
+        /// How many of its tabs match.
+        tabs: usize,
+        collapsed: bool,
+    },
+    Tab {
+        session: &'a str,
+        tab: &'a TabInfo,
+    },
+}
+
+/// A line of tree mode, a tab or one of the panes of an expanded tab.
+enum Node<'a> {
+    Tab {
+        tab: &'a TabInfo,
+        expanded: bool,
+        panes: usize,
+    },
+    Pane {
+        tab: &'a TabInfo,
+        pane: &'a PaneInfo,
+        /// The last pane of its tab, whose branch ends there.
+        last: bool,
+    },
+}
+
+/// What the keys currently drive, the tab list or one of the steps opened on top of it.
+#[derive(Default)]
+enum Submode {
+    #[default]
+    List,
+    Actions(Menu),
+    Rename {
+        position: usize,
+        name: String,
+    },
+    Confirm(Confirm),
+    /// Picking the tab's icon, `selected` indexes the configured icons after a first "none".
+    Icon {
+        position: usize,
+        selected: usize,
+    },
+    /// The tabs of every other session under their session, `selected` indexes
+    /// [`State::global_lines`].
+    Global {
+        selected: usize,
+    },
+    /// Zellij actions, `selected` indexes [`State::palette_actions`].
+    Palette {
+        selected: usize,
+    },
+    /// Zellij's keybindings matching the filter, nothing to select.
+    Cheatsheet,
+    /// A keybinding launching room the way it's configured now, reached from the cheatsheet.
+    Snippet,
+    /// The panes of every tab, `selected` indexes [`State::pane_entries`].
+    Panes {
+        selected: usize,
+    },
+    /// The tabs and the panes of the expanded ones, `selected` indexes [`State::tree_nodes`].
+    /// Tabs expanded or collapsed by hand are in `expanded`, the others are expanded while the
+    /// filter matches some of their panes.
+    Tree {
+        selected: usize,
+        expanded: BTreeMap<usize, bool>,
+    },
+    /// The configured projects, `selected` indexes [`State::projects`].
+    Projects {
+        selected: usize,
+    },
+    /// How much every tab got used.
+    Stats,
+    /// What room asked zellij for and what zellij answered.
+    Permissions,
+}
+
+#[derive(Default)]
+
+pub struct State {
+    config: Config,
+    /// What's wrong with the configuration, shown until the next key.
+    config_errors: Vec<String>,
+    /// The options as zellij handed them over, for [`Submode::Snippet`].
+    configuration: BTreeMap<String, String>,
+    /// The first instance ever explains itself until the next key.
+    onboarding: bool,
+    tabs: Vec<TabInfo>,
+    /// Panes of every tab, by tab position.
+    panes: HashMap<usize, Vec<PaneInfo>>,
+    filter: String,
+    selected: Option<usize>,
+    permissions: Option<PermissionStatus>,
+    tabs_received: bool,
+    jumplist: JumpList,
+    pins: TabSet,
+    protected: TabSet,
+    /// The tabs marked to be moved together, by name, for this instance only.
+    marked: BTreeSet<String>,
+    /// Lists the tabs the `ignore` patterns hide, until toggled back.
+    reveal_ignored: bool,
+    /// Draws every tab on two lines, starting from `density` and toggled with a key.
+    detailed: bool,
+    /// Text to put on the clipboard with the next frame.
+    clipboard: Option<String>,
+    /// Zellij's theme, which the zellij preset follows.
+    palette: Option<Palette>,
+    theme: Theme,
+    /// Frames of the selection's pulse left to draw, see [`PULSE_STEPS`].
+    pulse: u8,
+    /// Pane mode lists plugin panes too, starts from `show_plugin_panes`.
+    show_plugin_panes: bool,
+    pane_kind: PaneKind,
+    colors: TabLabels,
+    icons: TabLabels,
+    submode: Submode,
+    toast: Option<usize>,
+    /// When the toast's time is up, other timers firing before leave it shown.
+    toast_due: f64,
+    sort: Sort,
+    last_action: Option<TabAction>,
+    /// What went wrong last, e.g. going to a tab that doesn't exist or a file room couldn't
+    /// save. It's shown at the bottom of every mode until Esc dismisses it.
+    error: Option<String>,
+    /// A pane refresh timer is running, later pane updates wait for it.
+    pane_timer: bool,
+    /// Pane updates arrived while the timer was running.
+    panes_dirty: bool,
+    /// Every session but ours, as of the last session update.
+    sessions: Vec<SessionInfo>,
+    /// Whether room listens to session updates, which it only does while global mode is open.
+    following_sessions: bool,
+    /// Whether a session update came since room started listening.
+    sessions_received: bool,
+    /// When saved files were last handed to the worker.
+    flushed_at: f64,
+    /// A timer is running for the files saved too soon after the last flush.
+    flush_timer: bool,
+    /// The sessions global mode lists without their tabs, for this instance only.
+    collapsed_sessions: BTreeSet<String>,
+    /// The row under the mouse while a button is held, zellij doesn't report plain motion.
+    hovered: Option<usize>,
+    /// The tab being dragged to another place, by its position as of the last move.
+    dragged: Option<usize>,
+    /// Every tab's working directory by position, from the last dumped session layout.
+    tab_cwds: Vec<Option<PathBuf>>,
+    /// Where the session a tab is torn off to starts, while its layout gets written.
+    tear_off_cwd: Option<PathBuf>,
+    /// The branch checked out in each directory, `None` while git first runs or outside a
+    /// repository, along with when git was asked for it.
+    branches: HashMap<PathBuf, (Option<String>, f64)>,
+    session_name: Option<String>,
+    /// Zellij's keybindings, from the last mode update.
+    bindings: Vec<Binding>,
+    stats: Stats,
+    /// When room first saw each tab, standing in for its creation time.
+    created: Timestamps,
+    /// When every tab was last focused, as far as room saw.
+    last_active: Timestamps,
+    /// What every tab is matched on, by position.
+    search_strs: HashMap<usize, SearchStr>,
+    /// How far down the last frame was drawn, clicks are on the pane's lines.
+    frame_top: usize,
+    /// The tab (by position) on every line of the list as last drawn, see [`Frame::mark_tab`].
+    tab_lines: Vec<(usize, usize)>,
+    /// When the last key or click came in, and when the idle timer is due (unix seconds).
+    last_input: f64,
+    idle_due: Option<f64>,
+    /// Where room was opened from, zellij starts plugins in the focused pane's directory.
+    launch_cwd: PathBuf,
+    /// The tab selected when the last snapshot was saved, selected again once the tabs come.
+    restored_selection: Option<String>,
+}
+
+impl State {
+    fn score(&self, query: &Query, tab: &TabInfo) -> Option<i64> {
+        // refreshed with every tab update, so every tab has one
+        let search_str = self.search_strs.get(&tab.position)?;
+        let index_str = &search_str.index;
+        let filter = match query {
+            Query::Text(filter) => filter,
+            // a purely numeric query is an index, it shouldn't also match digits inside names
+            Query::Index(index) => {
+                return index_str
+                    .starts_with(index)
+                    .then(|| score::bonus(index_str, "", index));
+            }
+            Query::Range(start, end) => {
+                return (*start..=*end).contains(&(tab.position + 1)).then_some(0);
+            }
+            Query::Goto(index) => return (tab.position + 1 == *index).then_some(0),
+            Query::Command(_) => return Some(0),
+        };
+
+        let score = self.config.matcher.score(&search_str.haystack, filter)?
+            + score::bonus(index_str, &search_str.name_lowercase, filter)
+            + self.cwd_bonus(tab);
+
+        // an empty filter lists every tab, the threshold only weeds out weak matches of a query
+        if !filter.is_empty() && score < self.config.min_score {
+            return None;
+        }
+
+        let weights = &self.config.weights;
+        let mut score = score * weights.matched;
+        if weights.frecency != 0 {
+            let recent = self
+                .jumplist
+                .recent()
+                .iter()
+                .position(|name| *name == tab.name);
+            // picking a tab here is what says it's looked for here, visits count focusing it any
+            // other way too (and each switch a second time)
+            let switches = self.stats.get(&tab.name).switches;
+            score += score::frecency(switches, recent) * weights.frecency;
+        }
+        if weights.proximity != 0 {
+            if let Some(active) = self.tabs.iter().find(|tab| tab.active) {
+                score += score::proximity(tab.position, active.position) * weights.proximity;
+            }
+        }
+
+        Some(score)
+    }
+
+    fn viewable_tabs_iter(&self) -> impl Iterator<Item = &TabInfo> {
+        self.tabs_matching(&self.filter)
+    }
+
+    /// The first `count` listed tabs, without sorting the ones past them.
+    fn first_listed(&self, count: usize) -> Vec<&TabInfo> {
+        let mut tabs = self.ranked_tabs(&self.filter);
+        keep_first(&mut tabs, count);
+        tabs.into_iter().map(|(_, tab)| tab).collect()
+    }
+
+    /// The tab listed at `index`, found without sorting the list.
+    fn nth_listed(&self, index: usize) -> Option<&TabInfo> {
+        let mut tabs = self.ranked_tabs(&self.filter);
+        if index >= tabs.len() {
+            return None;
+        }
+        let (_, (_, tab), _) = tabs.select_nth_unstable_by_key(index, |(key, _)| *key);
+        Some(*tab)
+    }
+
+    /// Where the selected tab is listed (when it is), and how many tabs are.
+    fn selected_index(&self) -> (Option<usize>, usize) {
+        let tabs = self.ranked_tabs(&self.filter);
+        let index = tabs
+            .iter()
+            .find(|(_, tab)| Some(tab.position) == self.selected)
+            .map(|(selected, _)| tabs.iter().filter(|(key, _)| key < selected).count());
+        (index, tabs.len())
+    }
+
+    /// The tabs matching `filter` in the order they're listed.
+    fn tabs_matching<'a>(&'a self, filter: &str) -> impl Iterator<Item = &'a TabInfo> {
+        let mut tabs = self.ranked_tabs(filter);
+        tabs.sort_unstable_by_key(|(key, _)| *key);
+        tabs.into_iter().map(|(_, tab)| tab)
+    }
+
+    /// The listed tabs, split in runs of recent, pinned and other tabs. Only the first `count`
+    /// of a section are sorted and kept (more when the selection is further down it), the rest
+    /// can't make it on screen.
+    fn sections(&self, count: usize) -> Vec<Section<'_>> {
+        let mut runs: [Vec<(RankKey, &TabInfo)>; 3] = Default::default();
+        let kinds = [Kind::Recent, Kind::Pinned, Kind::Tabs];
+        for (key, tab) in self.ranked_tabs(&self.filter) {
+            let run = match key {
+                (recent, ..) if recent != usize::MAX => 0,
+                (_, false, ..) => 1,
+                _ => 2,
+            };
+            runs[run].push((key, tab));
+        }
+
+        kinds
+            .into_iter()
+            .zip(runs)
+            .filter(|(_, run)| !run.is_empty())
+            .map(|(kind, mut run)| {
+                let total = run.len();
+                let selected = run
+                    .iter()
+                    .find(|(_, tab)| Some(tab.position) == self.selected)
+                    .map(|(selected, _)| run.iter().filter(|(key, _)| key < selected).count());
+                keep_first(&mut run, count.max(selected.map_or(0, |index| index + 1)));
+                Section {
+                    kind,
+                    tabs: run.into_iter().map(|(_, tab)| tab).collect(),
+                    total,
+                }
+            })
+            .collect()
+    }
+
+    /// Every tab matching `filter` along with what it's ordered by, in no particular order.
+    fn ranked_tabs<'a>(&'a self, filter: &str) -> Vec<(RankKey, &'a TabInfo)> {
+        let filter = filter.to_lowercase();
+        let (attributes, filter) = query::split_attributes(&filter);
+        // `viec` finds `việc`, the filter only takes ASCII so there's no other way to type it
+        let filter = match self.config.fold_accents {
+            true => collate::fold(&filter),
+            false => filter,
+        };
+        let query = query::parse(&filter, self.config.strict_index);
+        let now = chrono::Utc::now().timestamp();
+        let name_ranks = match self.sort {
+            Sort::Name => self.name_ranks(),
+            _ => HashMap::new(),
+        };
+        let recent = if filter.is_empty() {
+            self.recent_tabs()
+        } else {
+            Vec::new()
+        };
+
+        self.tabs
+            .iter()
+            .filter(|tab| !(tab.active && self.config.active_tab == ActiveTab::Hide))
+            .filter(|tab| self.reveal_ignored || !self.is_ignored(&tab.name))
+            // tabs of this session have no session to match
+            .filter(|tab| attributes.sessions.is_empty() && self.has_attributes(tab, &attributes))
+            .filter_map(|tab| {
+                let score = self.score(&query, tab)?;
+                // the greatest first, tabs room hasn't seen yet count as brand new
+                let created = || self.created.get(&tab.name).unwrap_or(now);
+                let sort = match self.sort {
+                    Sort::Score => 0,
+                    Sort::Name => -name_ranks.get(&tab.position).copied().unwrap_or_default(),
+                    Sort::Panes => self.pane_count(tab.position) as i64,
+                    Sort::Oldest => -created(),
+                    Sort::Newest => created(),
+                };
+
+                let key = (
+                    recent
+                        .iter()
+                        .position(|position| *position == tab.position)
+                        .unwrap_or(usize::MAX),
+                    !self.pins.contains(&tab.name),
+                    tab.active && self.config.active_tab == ActiveTab::Bottom,
+                    std::cmp::Reverse(sort),
+                    std::cmp::Reverse(score),
+                    // ties stay in tab order
+                    tab.position,
+                );
+                Some((key, tab))
+            })
+            .collect()
+    }
+
+    /// The positions of the `recent_tabs` tabs last visited through room, the latest first,
+    /// listed above the others while the filter is empty. The active tab isn't one of them.
+    fn recent_tabs(&self) -> Vec<usize> {
+        self.jumplist
+            .recent()
+            .into_iter()
+            .filter_map(|name| self.tabs.iter().find(|tab| tab.name == name))
+            .filter(|tab| !tab.active)
+            .map(|tab| tab.position)
+            .take(self.config.recent_tabs)
+            .collect()
+    }
+
+    /// How many of the listed tabs are in the recent section, see [`State::recent_tabs`].
+    fn recent_count(&self) -> usize {
+        if !self.filter.is_empty() {
+            return 0;
+        }
+        self.recent_tabs().len()
+    }
+
+    /// Where every tab (by position) comes alphabetically, from 0.
+    fn name_ranks(&self) -> HashMap<usize, i64> {
+        let mut positions: Vec<_> = self.search_strs.keys().copied().collect();
+        positions.sort_by_key(|position| &self.search_strs[position].collation);
+        positions.into_iter().zip(0..).collect()
+    }
+
+    fn save_filter(&self) {
+        if self.config.remember_filter {
+            worker::write(FILTER_PATH, &self.filter);
+        }
+    }
+
+    /// Everything the configured features need, asked for when loading and again from the
+    /// permissions panel.
+    fn needed_permissions(&self) -> Vec<PermissionType> {
+        // we need the ReadApplicationState permission to receive the ModeUpdate and TabUpdate
+        // events
+        // we need the ChangeApplicationState permission to Change Zellij state (Panes, Tabs and UI)
+        // we need the OpenTerminalsOrPlugins permission for the palette's new panes
+        let mut permissions = vec![
+            PermissionType::ReadApplicationState,
+            PermissionType::ChangeApplicationState,
+            PermissionType::OpenTerminalsOrPlugins,
+        ];
+        // we need the MessageAndLaunchOtherPlugins permission to publish switches, but only
+        // bother the user with it when they asked for that
+        if self.config.publish_to.is_some() {
+            permissions.push(PermissionType::MessageAndLaunchOtherPlugins);
+        }
+        // and ReadCliPipes to answer queries from the command line
+        if self.config.serve_queries {
+            permissions.push(PermissionType::ReadCliPipes);
+            if self.config.publish_to.is_none() {
+                permissions.push(PermissionType::MessageAndLaunchOtherPlugins);
+            }
+        }
+        // same for RunCommands, only needed to ask git for branches, run the user's hook or move
+        // tabs
+        if self.config.show_git_branch
+            || self.config.on_select_command.is_some()
+            || self.config.enable_move
+        {
+            permissions.push(PermissionType::RunCommands);
+        }
+        permissions
+    }
+
+    /// Whether zellij runs room's commands, which it only does when one of the options asking
+    /// for them is set and the permissions were granted.
+    fn can_run_commands(&self) -> bool {
+        self.permissions == Some(PermissionStatus::Granted)
+            && self
+                .needed_permissions()
+                .contains(&PermissionType::RunCommands)
+    }
+
+    /// Whether `tab` matches every attribute token but the session ones, which are up to the
+    /// caller.
+    fn has_attributes(&self, tab: &TabInfo, attributes: &Attributes) -> bool {
+        let index = (tab.position + 1).to_string();
+        let color = self.colors.get(&tab.name);
+        let panes = self.panes.get(&tab.position).map_or(&[][..], Vec::as_slice);
+        let cwd = self
+            .tab_cwds
+            .get(tab.position)
+            .and_then(Option::as_ref)
+            .map(|cwd| cwd.to_string_lossy().to_lowercase());
+
+        attributes
+            .colors
+            .iter()
+            .all(|wanted| color.is_some_and(|color| color.starts_with(wanted)))
+            && attributes.commands.iter().all(|wanted| {
+                panes.iter().any(|pane| {
+                    // a command pane's title is the command too, until something renames it
+                    let command = pane.terminal_command.as_ref().unwrap_or(&pane.title);
+                    command.to_lowercase().contains(wanted)
+                })
+            })
+            && attributes
+                .dirs
+                .iter()
+                .all(|wanted| cwd.as_ref().is_some_and(|cwd| cwd.contains(wanted)))
+            && attributes
+                .indices
+                .iter()
+                .all(|wanted| index.starts_with(wanted))
+    }
+
+    /// With `boost_cwd`, tabs working near the directory room was opened from rank higher.
+    fn cwd_bonus(&self, tab: &TabInfo) -> i64 {
+        if !self.config.boost_cwd {
+            return 0;
+        }
+
+        match self.tab_cwds.get(tab.position) {
+            Some(Some(cwd)) => score::cwd_bonus(cwd, &self.launch_cwd),
+            _ => 0,
+        }
+    }
+
+    fn tab_color(&self, name: &str) -> Option<Color> {
+        self.colors.get(name).and_then(Color::from_name)
+    }
+
+    fn clear_filter(&mut self) {
+        self.filter.clear();
+        self.save_filter();
+
+        self.reset_selection();
+    }
+
+    /// The terminal panes of a tab, plugins like the tab bar (or room itself) don't count.
+    fn pane_count(&self, position: usize) -> usize {
+        self.panes.get(&position).map_or(0, |panes| {
+            panes.iter().filter(|pane| !pane.is_plugin).count()
+        })
+    }
+
+    /// Whether any pane details are drawn, otherwise pane updates don't need a render.
+    fn shows_panes(&self) -> bool {
+        self.sort == Sort::Panes
+            || self.config.show_pane_title
+            || self.config.show_exit_status
+            || self.detailed
+            || matches!(self.submode, Submode::Panes { .. } | Submode::Tree { .. })
+    }
+
+    /// How many panes of the tab at `position` ran a command that exited with an error.
+    fn failed_panes(&self, position: usize) -> usize {
+        self.panes.get(&position).map_or(0, |panes| {
+            panes
+                .iter()
+                .filter(|pane| pane.exited && pane.exit_status.is_some_and(|status| status != 0))
+                .count()
+        })
+    }
+
+    fn reset_selection(&mut self) {
+        self.selected = self.nth_listed(0).map(|tab| tab.position);
+    }
+
+    /// The tabs of the other sessions matching the filter, best match first, along with the
+    /// name of their session.
+    fn global_tabs(&self) -> Vec<(&str, &TabInfo)> {
+        let filter = self.filter.to_lowercase();
+        // the panes, directories and colors room knows are of this session's tabs only
+        let (attributes, filter) = query::split_attributes(&filter);
+        let mut tabs: Vec<_> = self
+            .sessions
+            .iter()
+            .filter(|session| {
+                let name = session.name.to_lowercase();
+                attributes
+                    .sessions
+                    .iter()
+                    .all(|wanted| name.contains(wanted))
+            })
+            .flat_map(|session| session.tabs.iter().map(move |tab| (session, tab)))
+            .filter(|(_, tab)| self.reveal_ignored || !self.is_ignored(&tab.name))
+            .filter(|(_, tab)| {
+                let index = (tab.position + 1).to_string();
+                attributes
+                    .indices
+                    .iter()
+                    .all(|wanted| index.starts_with(wanted))
+            })
+            .filter_map(|(session, tab)| {
+                let search_str = format!(
+                    "{}/{}: {}",
+                    session.name.to_lowercase(),
+                    tab.position + 1,
+                    tab.name.to_lowercase()
+                );
+                let score = self.config.matcher.score(&search_str, &filter)?;
+                Some((session.name.as_str(), tab, score))
+            })
+            .collect();
+        tabs.sort_by_key(|(_, _, score)| std::cmp::Reverse(*score));
+        tabs.into_iter()
+            .map(|(session, tab, _)| (session, tab))
+            .collect()
+    }
+
+    /// The lines of global mode: every session with a matching tab, the one with the best match
+    /// first, each followed by its matching tabs unless it's collapsed.
+    fn global_lines(&self) -> Vec<GlobalLine<'_>> {
+        let mut sessions: Vec<(&str, Vec<&TabInfo>)> = Vec::new();
+        for (session, tab) in self.global_tabs() {
+            match sessions.iter_mut().find(|(name, _)| *name == session) {
+                Some((_, tabs)) => tabs.push(tab),
+                None => sessions.push((session, vec![tab])),
+            }
+        }
+
+        let mut lines = Vec::new();
+        for (session, tabs) in sessions {
+            let collapsed = self.collapsed_sessions.contains(session);
+            lines.push(GlobalLine::Session {
+                name: session,
+                tabs: tabs.len(),
+                collapsed,
+            });
+            if !collapsed {
+                lines.extend(tabs.into_iter().map(|tab| GlobalLine::Tab { session, tab }));
+            }
+        }
+        lines
+    }
+
+    /// Keys of global mode: Enter attaches to the selected session, at the selected tab, Left
+    /// collapses the session and Right expands it again.
+    fn update_global(&mut self, key: Key, selected: usize) {
+        let picked = self.global_lines().get(selected).map(|line| match line {
+            GlobalLine::Session { name, .. } => (name.to_string(), None),
+            GlobalLine::Tab { session, tab } => (session.to_string(), Some(tab.position)),
+        });
+
+        match key {
+            key if keymap::is_enter(key) => {
+                if let Some((session, position)) = picked {
+                    // zellij focuses the tab as part of attaching, nothing is left for us to
+                    // finish once we're in the other session
+                    close();
+                    log::action(&format!("switch session={:?} tab={:?}", session, position));
+                    host::switch_session_with_focus(&session, position, None);
+                }
+            }
+            key if self.config.keymap.down(key) => self.set_global_selection(selected + 1),
+            key if self.config.keymap.up(key) => {
+                self.set_global_selection(selected.wrapping_sub(1))
+            }
+            Key::Right => {
+                if let Some((session, _)) = picked {
+                    self.collapsed_sessions.remove(&session);
+                }
+            }
+            Key::Left => {
+                if let Some((session, _)) = picked {
+                    // its tabs' lines go away, the selection moves up to the session
+                    let line = self.global_lines().iter().position(
+                        |line| matches!(line, GlobalLine::Session { name, .. } if *name == session),
+                    );
+                    self.collapsed_sessions.insert(session);
+                    self.set_global_selection(line.unwrap_or_default());
+                }
+            }
+            Key::Esc => self.submode = Submode::List,
+            key if keymap::matches(self.config.keymap.global, key) => self.submode = Submode::List,
+            Key::Backspace => {
+                self.filter.pop();
+                self.select_best_global();
+            }
+            Key::Char(c) if c.is_ascii() && !c.is_control() => {
+                self.filter.push(c);
+                self.select_best_global();
+            }
+            _ => (),
+        }
+    }
+
+    /// Selects the best match rather than its session, for Enter to go straight there.
+    fn select_best_global(&mut self) {
+        let line = self
+            .global_lines()
+            .iter()
+            .position(|line| matches!(line, GlobalLine::Tab { .. }));
+        self.set_global_selection(line.unwrap_or_default());
+    }
+
+    fn set_global_selection(&mut self, line: usize) {
+        if let Submode::Global { selected } = &mut self.submode {
+            *selected = line;
+        }
+    }
+
+    /// The panes of every tab matching the filter, best match first then in tab order. Plugin
+    /// panes (the tab bar, room itself...) are left out unless asked for.
+    fn pane_entries(&self) -> Vec<(&TabInfo, &PaneInfo)> {
+        let filter = self.filter.to_lowercase();
+        let mut panes: Vec<_> = self
+            .tabs
+            .iter()
+            .flat_map(|tab| {
+                let panes = self.panes.get(&tab.position).map(Vec::as_slice);
+                panes
+                    .unwrap_or_default()
+                    .iter()
+                    .map(move |pane| (tab, pane))
+            })
+            .filter(|(_, pane)| self.show_plugin_panes || !pane.is_plugin)
+            .filter(|(_, pane)| !pane.is_suppressed && self.pane_kind.includes(pane))
+            .filter_map(|(tab, pane)| {
+                let search_str = format!(
+                    "{}: {} {} {}",
+                    tab.position + 1,
+                    tab.name.to_lowercase(),
+                    pane.title.to_lowercase(),
+                    pane.terminal_command.as_deref().unwrap_or_default(),
+                );
+                let score = self.config.matcher.score(&search_str, &filter)?;
+                Some((tab, pane, score))
+            })
+            .collect();
+        panes.sort_by_key(|(tab, _, score)| (std::cmp::Reverse(*score), tab.position));
+        panes
+            .into_iter()
+            .map(|(tab, pane, _)| (tab, pane))
+            .collect()
+    }
+
+    /// The lines of tree mode: every tab matching the filter in order, or holding a pane that
+    /// does, each followed by its panes when expanded. A branch the filter opened only shows the
+    /// panes matching it.
+    fn tree_nodes(&self) -> Vec<Node<'_>> {
+        let Submode::Tree { expanded, .. } = &self.submode else {
+            return Vec::new();
+        };
+        let filter = self.filter.to_lowercase();
+        let matches = |text: String| {
+            filter.is_empty()
+                || self
+                    .config
+                    .matcher
+                    .score(&text.to_lowercase(), &filter)
+                    .is_some()
+        };
+
+        let mut tabs: Vec<&TabInfo> = self
+            .tabs
+            .iter()
+            .filter(|tab| self.reveal_ignored || !self.is_ignored(&tab.name))
+            .collect();
+        tabs.sort_by_key(|tab| tab.position);
+
+        let mut nodes = Vec::new();
+        for tab in tabs {
+            let panes: Vec<&PaneInfo> = self
+                .panes
+                .get(&tab.position)
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .filter(|pane| (self.show_plugin_panes || !pane.is_plugin) && !pane.is_suppressed)
+                .collect();
+            let matching: Vec<&PaneInfo> = panes
+                .iter()
+                .copied()
+                .filter(|pane| {
+                    let command = pane.terminal_command.as_deref().unwrap_or_default();
+                    matches(format!("{} {}", pane.title, command))
+                })
+                .collect();
+            if !matches(format!("{}: {}", tab.position + 1, tab.name)) && matching.is_empty() {
+                continue;
+            }
+
+            let children = match expanded.get(&tab.position) {
+                Some(true) => panes.clone(),
+                Some(false) => Vec::new(),
+                None if filter.is_empty() => Vec::new(),
+                None => matching,
+            };
+            nodes.push(Node::Tab {
+                tab,
+                expanded: !children.is_empty(),
+                panes: panes.len(),
+            });
+            let count = children.len();
+            nodes.extend(
+                children
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, pane)| Node::Pane {
+                        tab,
+                        pane,
+                        last: index + 1 == count,
+                    }),
+            );
+        }
+        nodes
+    }
+
+    /// Keys of tree mode: Right expands the selected tab, Left collapses it (or the tab of the
+    /// selected pane), Enter switches to the tab or focuses the pane.
+    fn update_tree(&mut self, key: Key, selected: usize) {
+        // (tab position, pane id and whether it's a plugin) of the selected line
+        let picked = self.tree_nodes().get(selected).map(|node| match node {
+            Node::Tab { tab, .. } => (tab.position, None),
+            Node::Pane { tab, pane, .. } => (tab.position, Some((pane.id, pane.is_plugin))),
+        });
+
+        if keymap::is_enter(key) {
+            match picked {
+                Some((position, None)) => self.switch_to(position, false),
+                Some((_, Some((id, is_plugin)))) => {
+                    close();
+                    log::action(&format!("focus pane={} plugin={}", id, is_plugin));
+                    if is_plugin {
+                        host::focus_plugin_pane(id, false);
+                    } else {
+                        host::focus_terminal_pane(id, false);
+                    }
+                }
+                None => (),
+            }
+            return;
+        }
+
+        let Submode::Tree { selected, expanded } = &mut self.submode else {
+            return;
+        };
+        match key {
+            key if self.config.keymap.down(key) => *selected += 1,
+            key if self.config.keymap.up(key) => *selected = selected.wrapping_sub(1),
+            Key::Right => {
+                if let Some((position, None)) = picked {
+                    expanded.insert(position, true);
+                }
+            }
+            Key::Left => {
+                if let Some((position, _)) = picked {
+                    expanded.insert(position, false);
+                    // a pane's line is gone with its branch, the selection goes up to its tab
+                    let tab_line = self.tree_nodes().iter().position(
+                        |node| matches!(node, Node::Tab { tab, .. } if tab.position == position),
+                    );
+                    if let Submode::Tree { selected, .. } = &mut self.submode {
+                        *selected = tab_line.unwrap_or_default();
+                    }
+                }
+            }
+            Key::Esc => self.submode = Submode::List,
+            key if keymap::matches(self.config.keymap.tree, key) => self.submode = Submode::List,
+            Key::Backspace => {
+                self.filter.pop();
+                *selected = 0;
+            }
+            Key::Char(c) if c.is_ascii() && !c.is_control() => {
+                self.filter.push(c);
+                *selected = 0;
+            }
+            _ => (),
+        }
+    }
+
+    /// The configured projects matching the filter, best match first.
+    fn projects(&self) -> Vec<&Project> {
+        let filter = self.filter.to_lowercase();
+        let mut projects: Vec<_> = self
+            .config
+            .projects
+            .iter()
+            .filter_map(|project| {
+                let score = self
+                    .config
+                    .matcher
+                    .score(&project.name.to_lowercase(), &filter)?;
+                Some((project, score))
+            })
+            .collect();
+        projects.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        projects.into_iter().map(|(project, _)| project).collect()
+    }
+
+    /// The palette's actions matching the filter, best match first.
+    fn palette_actions(&self) -> Vec<PaletteAction> {
+        let filter = self.filter.to_lowercase();
+        let mut actions: Vec<_> = PaletteAction::ALL
+            .iter()
+            .filter_map(|action| {
+                let score = self.config.matcher.score(action.label(), &filter)?;
+                Some((*action, score))
+            })
+            .collect();
+        actions.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        actions.into_iter().map(|(action, _)| action).collect()
+    }
+
+    /// The answer to a `room:query` pipe, as JSON. Directories are only known once zellij sent
+    /// the session's layout, until then they're null.
+    fn answer_query(&self, payload: Option<&str>) -> serde_json::Value {
+        let query: serde_json::Value = match payload.map(serde_json::from_str).transpose() {
+            Ok(query) => query.unwrap_or_default(),
+            Err(error) => return serde_json::json!({ "error": error.to_string() }),
+        };
+
+        let kind = query["kind"].as_str().unwrap_or("tabs");
+        if kind != "tabs" {
+            return serde_json::json!({ "error": format!("unknown kind '{}'", kind) });
+        }
+
+        let filter = query["filter"].as_str().unwrap_or("");
+        let tabs: Vec<_> = self
+            .tabs_matching(filter)
+            .map(|tab| {
+                let cwd = self.tab_cwds.get(tab.position).and_then(Option::as_ref);
+                serde_json::json!({
+                    "name": tab.name,
+                    "position": tab.position,
+                    "active": tab.active,
+                    "panes": self.pane_count(tab.position),
+                    "cwd": cwd,
+                })
+            })
+            .collect();
+
+        serde_json::json!({ "tabs": tabs })
+    }
+
+    /// Explains why an index query led nowhere, instead of switching to a tab zellij doesn't
+    /// have (or creating a tab named after a number).
+    fn missing_index_error(&self) -> Option<String> {
+        let filter = self.filter.to_lowercase();
+        match query::parse(&filter, self.config.strict_index) {
+            Query::Goto(index) => Some(self.no_tab_error(index)),
+            Query::Index(index) => Some(self.no_tab_error(index)),
+            _ => None,
+        }
+    }
+
+    fn no_tab_error(&self, index: impl std::fmt::Display) -> String {
+        match self.tabs.len() {
+            1 => format!("no tab {}, there is only 1 tab", index),
+            count => format!("no tab {}, there are only {} tabs", index, count),
+        }
+    }
+
+    /// Whether the filter line holds a `:` command rather than a query.
+    fn typing_command(&self) -> bool {
+        matches!(
+            query::parse(&self.filter, self.config.strict_index),
+            Query::Command(_)
+        )
+    }
+
+    /// Runs the `:` command in the filter line, clearing it once done.
+    fn run_command_line(&mut self) {
+        let Query::Command(line) = query::parse(&self.filter, self.config.strict_index) else {
+            return;
+        };
+
+        match command::parse(line).and_then(|command| self.execute(command)) {
+            Ok(()) => {
+                self.filter.clear();
+                self.save_filter();
+            }
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    fn execute(&mut self, command: Command) -> Result<(), String> {
+        // 1 indexed like the list, or the selected tab when no index was given
+        let target = |index: Option<usize>| match index {
+            Some(index) if index <= self.tabs.len() => Ok(index - 1),
+            Some(index) => Err(self.no_tab_error(index)),
+            None => self.selected.ok_or_else(|| "no tab selected".to_string()),
+        };
+
+        match command {
+            Command::Rename(name) => {
+                let position = target(None)?;
+                self.rename(position, &name);
+            }
+            Command::Close(index) => {
+                let position = target(index)?;
+                self.run_action(TabAction::Close, position);
+            }
+            Command::Pin(index) => {
+                let position = target(index)?;
+                self.run_action(TabAction::Pin, position);
+            }
+            Command::Protect(index) => {
+                let position = target(index)?;
+                self.run_action(TabAction::Protect, position);
+            }
+            Command::Move(from, to) => {
+                let (from, to) = (target(Some(from))?, target(Some(to))?);
+                self.move_tab(from, to)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Moves the marked tabs (the selected one when none is) one step to the right or the left,
+    /// as a block keeping their order. The selection follows the tab it was on.
+    fn shift_marked(&mut self, right: bool) -> Result<(), String> {
+        if !self.config.enable_move {
+            return Err("moving tabs needs enable_move to be set".to_string());
+        }
+        let Some(session) = &self.session_name else {
+            return Err("zellij hasn't told us the session's name yet".to_string());
+        };
+
+        let mut positions: Vec<usize> = self
+            .tabs
+            .iter()
+            .filter(|tab| self.marked.contains(&tab.name))
+            .map(|tab| tab.position)
+            .collect();
+        if positions.is_empty() {
+            positions.extend(self.selected);
+        }
+        positions.sort_unstable();
+        // the tab leading the way moves first so the others follow into the room it made
+        if right {
+            positions.reverse();
+        }
+
+        let count = self.tabs.len();
+        match positions.first() {
+            None => return Ok(()),
+            Some(0) if !right => return Err("the tabs are already first".to_string()),
+            Some(last) if right && last + 1 >= count => {
+                return Err("the tabs are already last".to_string())
+            }
+            _ => (),
+        }
+
+        let current = self
+            .tabs
+            .iter()
+            .find(|tab| tab.active)
+            .map_or(0, |tab| tab.position);
+        let back = cli::shifted_position(current, &positions, right, count);
+        cli::shift_tabs(session, &positions, right, back);
+        self.selected = self
+            .selected
+            .map(|selected| cli::shifted_position(selected, &positions, right, count));
+        Ok(())
+    }
+
+    /// Moves the tab at `position` one step to the right (or the left), the selection following
+    /// it.
+    fn move_one_step(&mut self, position: usize, right: bool) -> Result<(), String> {
+        let to = match right {
+            true if position + 1 < self.tabs.len() => position + 1,
+            false if position > 0 => position - 1,
+            true => return Err("the tab is already last".to_string()),
+            false => return Err("the tab is already first".to_string()),
+        };
+        self.move_tab(position, to)?;
+        self.selected = self
+            .selected
+            .map(|selected| cli::moved_position(selected, position, to));
+        Ok(())
+    }
+
+    /// Moves the tab at `from` to `to`, staying on the tab we're on.
+    fn move_tab(&self, from: usize, to: usize) -> Result<(), String> {
+        if !self.config.enable_move {
+            return Err("moving tabs needs enable_move to be set".to_string());
+        }
+        let Some(session) = &self.session_name else {
+            return Err("zellij hasn't told us the session's name yet".to_string());
+        };
+
+        let current = self
+            .tabs
+            .iter()
+            .find(|tab| tab.active)
+            .map_or(from, |tab| tab.position);
+        cli::move_tab(session, from, to, cli::moved_position(current, from, to));
+        Ok(())
+    }
+
+    fn rename(&mut self, position: usize, name: &str) {
+        if name.trim().is_empty() {
+            self.error = Some("a tab's name can't be empty".to_string());
+            return;
+        }
+        log::action(&format!("rename position={} name={:?}", position + 1, name));
+        host::rename_tab(position as u32 + 1, name);
+
+        let old_name = self
+            .tabs
+            .iter()
+            .find(|tab| tab.position == position)
+            .map(|tab| tab.name.clone());
+        if let Some(old_name) = old_name {
+            self.pins.rename(&old_name, name);
+            self.protected.rename(&old_name, name);
+            self.stats.rename(&old_name, name);
+            self.created.rename(&old_name, name);
+            self.last_active.rename(&old_name, name);
+            self.colors.rename(&old_name, name);
+            self.icons.rename(&old_name, name);
+            if self.marked.remove(&old_name) {
+                self.marked.insert(name.to_string());
+            }
+        }
+    }
+
+    /// Where `tab` comes among the tabs sharing its name, from 1, or `None` when its name is
+    /// its own.
+    fn duplicate_number(&self, tab: &TabInfo) -> Option<usize> {
+        let mut namesakes = self.tabs.iter().filter(|other| other.name == tab.name);
+        namesakes.nth(1)?;
+        Some(
+            self.tabs
+                .iter()
+                .filter(|other| other.name == tab.name && other.position < tab.position)
+                .count()
+                + 1,
+        )
+    }
+
+    /// What tells a tab apart from the others with its name: its directory when it's known,
+    /// else what its focused pane runs or is titled.
+    fn duplicate_hint(&self, tab: &TabInfo) -> Option<String> {
+        if let Some(cwd) = self.tab_cwds.get(tab.position).and_then(Option::as_ref) {
+            return Some(cwd.display().to_string());
+        }
+
+        let pane = self.focused_pane(tab.position)?;
+        Some(
+            pane.terminal_command
+                .clone()
+                .unwrap_or_else(|| pane.title.clone()),
+        )
+    }
+
+    /// Moves the selection to the next listed tab named like the selected one, wrapping around.
+    fn select_duplicate(&mut self) -> bool {
+        let Some(name) = self
+            .selected
+            .and_then(|position| self.tabs.iter().find(|tab| tab.position == position))
+            .map(|tab| tab.name.clone())
+        else {
+            return false;
+        };
+
+        let namesakes: Vec<usize> = self
+            .viewable_tabs_iter()
+            .filter(|tab| tab.name == name)
+            .map(|tab| tab.position)
+            .collect();
+        let next = namesakes
+            .iter()
+            .position(|position| Some(*position) == self.selected)
+            .map_or(0, |index| (index + 1) % namesakes.len());
+        let previous = self.selected;
+        self.selected = namesakes.get(next).copied().or(previous);
+        self.selected != previous
+    }
+
+    /// Extra details drawn dimmed after a tab's name, empty when there's nothing to add.
+    fn metadata(&self, tab: &TabInfo) -> String {
+        let mut metadata = String::new();
+        if tab.is_fullscreen_active {
+            metadata.push_str(" [zoomed]");
+        }
+        if self.duplicate_number(tab).is_some() {
+            if let Some(hint) = self.duplicate_hint(tab) {
+                metadata.push_str(&format!(" in {}", hint));
+            }
+        }
+
+        if self.config.show_swap_layout {
+            if let Some(layout) = &tab.active_swap_layout_name {
+                metadata.push_str(&format!(" [{}]", layout));
+            }
+        }
+
+        if self.config.show_pane_title {
+            if let Some(pane) = self.focused_pane(tab.position) {
+                metadata.push_str(&format!(" - {}", pane.title));
+            }
+        }
+
+        if self.config.show_git_branch {
+            let branch = self
+                .tab_cwds
+                .get(tab.position)
+                .and_then(Option::as_ref)
+                .and_then(|cwd| self.branches.get(cwd)?.0.as_ref());
+            if let Some(branch) = branch {
+                metadata.push_str(&format!(" ⎇ {}", branch));
+            }
+        }
+
+        metadata
+    }
+
+    /// Asks git for the branch of every tab's directory it wasn't asked about in the last
+    /// `BRANCH_MAX_AGE_SECS`, or about all of them with `refresh`, since a checkout in a tab
+    /// changes it. The branch known until now stays shown while git runs.
+    fn fetch_branches(&mut self, refresh: bool) {
+        let now = now_secs();
+        for cwd in self.tab_cwds.iter().flatten() {
+            let asked_at = self.branches.get(cwd).map(|(_, asked_at)| *asked_at);
+            if asked_at.is_some_and(|asked_at| !refresh && now - asked_at < BRANCH_MAX_AGE_SECS) {
+                continue;
+            }
+
+            self.branches.entry(cwd.clone()).or_insert((None, now)).1 = now;
+            let context = BTreeMap::from([(
+                GIT_BRANCH_CONTEXT.to_string(),
+                cwd.to_string_lossy().into_owned(),
+            )]);
+            host::run_command_with_env_variables_and_cwd(
+                &["git", "rev-parse", "--abbrev-ref", "HEAD"],
+                BTreeMap::new(),
+                cwd.clone(),
+                context,
+            );
+        }
+    }
+
+    /// The terminal pane a tab would show focused, preferring the tiled one since floating panes
+    /// may be hidden.
+    fn focused_pane(&self, position: usize) -> Option<&PaneInfo> {
+        let mut focused = self
+            .panes
+            .get(&position)?
+            .iter()
+            .filter(|pane| pane.is_focused && !pane.is_plugin);
+        let first = focused.next()?;
+        Some(if first.is_floating {
+            focused.find(|pane| !pane.is_floating).unwrap_or(first)
+        } else {
+            first
+        })
+    }
+
+    fn active_tab_name(&self) -> Option<&str> {
+        self.tabs
+            .iter()
+            .find(|tab| tab.active)
+            .map(|tab| tab.name.as_str())
+    }
+
+    /// Switches to the tab at `position`, remembering the visit in the jumplist. With
+    /// `keep_open` room isn't closed, so launching it again (with zellij's `move_to_focused_tab`)
+    /// brings back the same filter and selection.
+    fn switch_to(&mut self, position: usize, keep_open: bool) {
+        let Some(tab) = self.tabs.iter().find(|tab| tab.position == position) else {
+            return;
+        };
+
+        let from = self.active_tab_name().map(String::from);
+        self.jumplist.record(from.as_deref(), &tab.name);
+        self.jumplist.save();
+        self.stats.switched(&tab.name);
+        self.stats.save();
+
+        self.leave_for(position, keep_open);
+    }
+
+    /// The position of the tab used the most according to the stats, the active one aside.
+    fn most_used_tab(&self) -> Option<usize> {
+        self.tabs
+            .iter()
+            .filter(|tab| !tab.active)
+            .map(|tab| (tab, self.stats.get(&tab.name)))
+            .filter(|(_, stats)| stats.visits > 0)
+            // the first tab wins a tie
+            .min_by_key(|(tab, stats)| {
+                (
+                    std::cmp::Reverse((stats.visits, stats.focus_secs)),
+                    tab.position,
+                )
+            })
+            .map(|(tab, _)| tab.position)
+    }
+
+    /// Selects the next (or previous) match and shows its tab right away, keeping room open with
+    /// the same filter. Passing through isn't a visit, so the jumplist and the stats are left
+    /// alone.
+    fn walk(&mut self, backward: bool) {
+        if backward {
+            self.select_up();
+        } else {
+            self.select_down();
+        }
+        if let Some(position) = self.selected {
+            self.leave(position, true);
+        }
+    }
+
+    /// Walks the jumplist backward (or forward) and switches to the tab found there.
+    fn jump(&mut self, backward: bool) {
+        let current = self.active_tab_name().map(String::from);
+        let tabs = &self.tabs;
+        let exists = |name: &str| tabs.iter().any(|tab| tab.name == name);
+        let target = if backward {
+            self.jumplist.back(current.as_deref(), exists)
+        } else {
+            self.jumplist.forward(exists)
+        };
+
+        let Some(position) = target.and_then(|name| {
+            tabs.iter()
+                .find(|tab| tab.name == name)
+                .map(|tab| tab.position)
+        }) else {
+            return;
+        };
+
+        self.jumplist.save();
+        self.leave_for(position, false);
+    }
+
+    /// Does the actual switch once the bookkeeping is done. With `confirm_toast_ms` set the
+    /// toast is shown first, and the timer finishes the switch.
+    fn leave_for(&mut self, position: usize, keep_open: bool) {
+        if let Some(name) = self
+            .tabs
+            .iter()
+            .find(|tab| tab.position == position)
+            .map(|tab| tab.name.clone())
+        {
+            self.note_focus(&name, chrono::Utc::now().timestamp());
+        }
+
+        if self.config.confirm_toast_ms > 0 && !keep_open {
+            let secs = self.config.confirm_toast_ms as f64 / 1000.0;
+            self.toast = Some(position);
+            self.toast_due = now_secs() + secs;
+            host::set_timeout(secs);
+        } else {
+            self.leave(position, keep_open);
+        }
+    }
+
+    /// Credits the stats and the last active times with `name` getting focused at `now`.
+    fn note_focus(&mut self, name: &str, now: i64) {
+        let previous = self.stats.focused().map(String::from);
+        if self.stats.focus(name, now) {
+            self.stats.save();
+            // the tab left behind was active until now
+            for name in previous.as_deref().into_iter().chain([name]) {
+                self.last_active.set(name, now);
+            }
+            self.last_active.save();
+        }
+    }
+
+    fn leave(&self, position: usize, keep_open: bool) {
+        let tab = self.tabs.iter().find(|tab| tab.position == position);
+        let from = self.active_tab_name();
+        if let (Some(destination), Some(tab)) = (&self.config.publish_to, tab) {
+            pipes::publish(destination, pipes::PRE_SWITCH, tab, from);
+        }
+
+        if !keep_open {
+            close();
+        }
+        log::action(&format!(
+            "switch to={} keep_open={}",
+            position + 1,
+            keep_open
+        ));
+        host::switch_tab_to(position as u32 + 1);
+        // landing in a fullscreen pane hides the rest of the tab without saying so
+        if self.config.unzoom_on_switch && tab.is_some_and(|tab| tab.is_fullscreen_active) {
+            host::toggle_focus_fullscreen();
+        }
+
+        if let (Some(destination), Some(tab)) = (&self.config.publish_to, tab) {
+            pipes::publish(destination, pipes::POST_SWITCH, tab, from);
+        }
+        if let (Some(command), Some(tab)) = (&self.config.on_select_command, tab) {
+            hooks::run_on_select(command, tab);
+        }
+    }
+
+    /// Closes room once nothing was typed or clicked for `auto_close_secs`, otherwise waits for
+    /// what's left of them. Timers can't be told apart, so any of them may get here early.
+    fn check_idle(&mut self) {
+        let (Some(secs), Some(due)) = (self.config.auto_close_secs, self.idle_due) else {
+            return;
+        };
+        let now = now_secs();
+        if now < due {
+            return;
+        }
+
+        let idle_for = now - self.last_input;
+        if idle_for >= secs {
+            close();
+        } else {
+            host::set_timeout(secs - idle_for);
+            self.idle_due = Some(now + secs - idle_for);
+        }
+    }
+
+    /// Finishes the switch the toast was announcing.
+    fn dismiss_toast(&mut self) {
+        if let Some(position) = self.toast.take() {
+            self.leave(position, false);
+        }
+    }
+
+    /// Closes the tabs at `positions`. Zellij can only close the focused tab, so we visit each
+    /// one and come back to where we were.
+    fn close_tabs(&self, mut positions: Vec<usize>) {
+        let current = self
+            .tabs
+            .iter()
+            .find(|tab| tab.active)
+            .map(|tab| tab.position);
+
+        // going right to left keeps the positions still to close valid
+        positions.sort_unstable_by(|a, b| b.cmp(a));
+        positions.dedup();
+        for position in &positions {
+            log::action(&format!("close tab={}", position + 1));
+            host::switch_tab_to(*position as u32 + 1);
+            host::close_focused_tab();
+        }
+
+        if let Some(current) = current.filter(|current| !positions.contains(current)) {
+            // everything after a closed tab moved one slot to the left
+            let shift = positions
+                .iter()
+                .filter(|position| **position < current)
+                .count();
+            host::switch_tab_to((current - shift) as u32 + 1);
+        }
+    }
+
+    /// Asks first when any of the tabs is protected, or when `always_ask` says so.
+    fn request_close(&mut self, positions: Vec<usize>, question: String, always_ask: bool) {
+        let protected = self
+            .tabs
+            .iter()
+            .filter(|tab| positions.contains(&tab.position) && self.is_protected(&tab.name))
+            .count();
+
+        if protected == 0 && !always_ask {
+            self.close_tabs(positions);
+            return;
+        }
+
+        let question = match protected {
+            0 => question,
+            1 => format!("{} (1 is protected)", question),
+            _ => format!("{} ({} are protected)", question, protected),
+        };
+        self.submode = Submode::Confirm(Confirm::new(question, Destructive::CloseTabs(positions)));
+    }
+
+    fn run_destructive(&mut self, action: Destructive) {
+        match action {
+            Destructive::CloseTabs(positions) => self.close_tabs(positions),
+        }
+    }
+
+    fn run_action(&mut self, action: TabAction, position: usize) {
+        let Some(tab) = self.tabs.iter().find(|tab| tab.position == position) else {
+            return;
+        };
+
+        if action.repeatable() {
+            self.last_action = Some(action);
+        }
+
+        match action {
+            TabAction::Switch => self.switch_to(position, false),
+            TabAction::Close => {
+                let question = format!("close {}:{}?", position + 1, tab.name);
+                self.request_close(vec![position], question, self.config.confirm_close);
+            }
+            TabAction::CloseOthers => {
+                let others: Vec<usize> = self
+                    .tabs
+                    .iter()
+                    .map(|tab| tab.position)
+                    .filter(|other| *other != position)
+                    .collect();
+                let question = match others.len() {
+                    1 => "close 1 other tab?".to_string(),
+                    count => format!("close {} other tabs?", count),
+                };
+                self.request_close(others, question, true);
+            }
+            TabAction::Rename => {
+                self.submode = Submode::Rename {
+                    position,
+                    name: tab.name.clone(),
+                }
+            }
+            TabAction::Pin => {
+                let name = tab.name.clone();
+                self.pins.toggle(&name);
+                self.pins.save();
+            }
+            TabAction::Protect => {
+                let name = tab.name.clone();
+                self.protected.toggle(&name);
+                self.protected.save();
+            }
+            TabAction::Icon => {
+                self.submode = Submode::Icon {
+                    position,
+                    selected: 0,
+                }
+            }
+            TabAction::Color => {
+                let name = tab.name.clone();
+                let color = Color::next(self.tab_color(&name));
+                self.colors.set(&name, color.map(Color::name));
+                self.colors.save();
+            }
+            // the new session starts in the tab's directory running its focused command anew,
+            // which takes a layout file written by a command, without RunCommands it only gets a
+            // shell. Protected tabs are copied rather than moved
+            TabAction::TearOff => {
+                let name = tab.name.clone();
+                let cwd = self.tab_cwds.get(position).cloned().flatten();
+                let command = self
+                    .focused_pane(position)
+                    .and_then(|pane| pane.terminal_command.clone())
+                    .filter(|_| self.can_run_commands());
+                if !self.is_protected(&name) {
+                    self.close_tabs(vec![position]);
+                }
+                match command {
+                    // room stays open for the file's path, see `Event::RunCommandResult`
+                    Some(command) => {
+                        let project = Project {
+                            name: name.clone(),
+                            cwd: cwd.clone(),
+                            layout: None,
+                            command: Some(command),
+                        };
+                        self.tear_off_cwd = cwd;
+                        cli::write_layout(&name, &project.layout_kdl());
+                    }
+                    None => {
+                        close();
+                        host::switch_session_with_layout(
+                            Some(&name),
+                            LayoutInfo::BuiltIn("default".to_string()),
+                            cwd,
+                        );
+                    }
+                }
+            }
+            TabAction::MoveLeft | TabAction::MoveRight => {
+                let right = action == TabAction::MoveRight;
+                if let Err(error) = self.move_one_step(position, right) {
+                    self.error = Some(error);
+                }
+            }
+            TabAction::Swap => {
+                if let Err(error) = self.swap_with_active(position) {
+                    self.error = Some(error);
+                }
+            }
+            // zellij doesn't let plugins move a pane to another tab, so the pane is opened again
+            // instead: its command runs anew, in the tab's directory when room knows it
+            TabAction::Borrow => {
+                let command = self
+                    .focused_pane(position)
+                    .and_then(|pane| pane.terminal_command.clone());
+                close();
+                self.reopen_pane(position, command.as_deref(), true);
+            }
+            // the same goes for every pane of the tab, once they're all opened again here the
+            // tab closes, unless it's protected
+            TabAction::Merge => {
+                if tab.active {
+                    self.error = Some("the tab is already the current one".to_string());
+                    return;
+                }
+                let name = tab.name.clone();
+                let commands: Vec<Option<String>> = self
+                    .panes
+                    .get(&position)
+                    .into_iter()
+                    .flatten()
+                    .filter(|pane| !pane.is_plugin)
+                    .map(|pane| pane.terminal_command.clone())
+                    .collect();
+                if commands.is_empty() {
+                    self.error = Some("zellij hasn't told us the tab's panes yet".to_string());
+                    return;
+                }
+
+                close();
+                for command in &commands {
+                    self.reopen_pane(position, command.as_deref(), self.config.merge_floating);
+                }
+                if !self.is_protected(&name) {
+                    self.close_tabs(vec![position]);
+                }
+            }
+        }
+    }
+
+    /// Opens a pane of the tab at `position` again in the current tab, running its `command`
+    /// (a shell without one) in the tab's directory when room knows it.
+    fn reopen_pane(&self, position: usize, command: Option<&str>, floating: bool) {
+        let cwd = self
+            .tab_cwds
+            .get(position)
+            .cloned()
+            .flatten()
+            .unwrap_or_else(|| self.launch_cwd.clone());
+        let mut words = command.unwrap_or_default().split_whitespace();
+        log::action(&format!(
+            "open pane command={:?} floating={}",
+            command, floating
+        ));
+
+        match (words.next(), floating) {
+            (Some(program), _) => {
+                let command = CommandToRun {
+                    path: PathBuf::from(program),
+                    args: words.map(String::from).collect(),
+                    cwd: Some(cwd),
+                };
+                if floating {
+                    host::open_command_pane_floating(command, None);
+                } else {
+                    host::open_command_pane(command);
+                }
+            }
+            (None, true) => host::open_terminal_floating(cwd, None),
+            (None, false) => host::open_terminal(cwd),
+        }
+    }
+
+    /// Swaps the tab at `position` with the active one, which stays focused at its new place.
+    fn swap_with_active(&mut self, position: usize) -> Result<(), String> {
+        if !self.config.enable_move {
+            return Err("moving tabs needs enable_move to be set".to_string());
+        }
+        let Some(session) = &self.session_name else {
+            return Err("zellij hasn't told us the session's name yet".to_string());
+        };
+        let Some(active) = self.tabs.iter().find(|tab| tab.active) else {
+            return Ok(());
+        };
+
+        let (a, b) = (active.position.min(position), active.position.max(position));
+        if a != b {
+            cli::swap_tabs(session, a, b, position);
+            self.selected = Some(active.position);
+        }
+        Ok(())
+    }
+
+    /// Tabs matching an `ignore` pattern, hidden from the list unless revealed.
+    fn is_ignored(&self, name: &str) -> bool {
+        self.config
+            .ignore_patterns
+            .iter()
+            .any(|pattern| pattern.is_match(name))
+    }
+
+    /// Protected tabs, toggled from the menu or matching a `protected` pattern, are only closed
+    /// after a confirmation.
+    fn is_protected(&self, name: &str) -> bool {
+        self.protected.contains(name)
+            || self
+                .config
+                .protected_patterns
+                .iter()
+                .any(|pattern| pattern.is_match(name))
+    }
+
+    /// Handles a key while one of the submodes (actions menu, rename, confirmation, other
+    /// sessions, palette, cheatsheet, stats) is open.
+    fn update_submode(&mut self, key: Key) {
+        if key == Key::Ctrl('c') {
+            close();
+            return;
+        }
+
+        match &mut self.submode {
+            Submode::List => (),
+            Submode::Actions(menu) => match key {
+                key if self.config.keymap.down(key) => menu.select_down(),
+                key if self.config.keymap.up(key) => menu.select_up(),
+                key if self.config.keymap.confirms(key) => {
+                    let (action, position) = (menu.action(), menu.position);
+                    self.submode = Submode::List;
+                    self.run_action(action, position);
+                }
+                Key::Esc => self.submode = Submode::List,
+                key if keymap::matches(self.config.keymap.actions, key) => {
+                    self.submode = Submode::List
+                }
+                _ => (),
+            },
+            Submode::Icon { position, selected } => match key {
+                // the first choice is no icon at all
+                key if self.config.keymap.down(key) => {
+                    *selected = (*selected + 1) % (self.config.icons.len() + 1)
+                }
+                key if self.config.keymap.up(key) => {
+                    *selected = selected.checked_sub(1).unwrap_or(self.config.icons.len())
+                }
+                key if keymap::is_enter(key) => {
+                    let tab = self.tabs.iter().find(|tab| tab.position == *position);
+                    if let Some(name) = tab.map(|tab| tab.name.clone()) {
+                        let icon = selected
+                            .checked_sub(1)
+                            .and_then(|index| self.config.icons.get(index));
+                        self.icons.set(&name, icon.map(String::as_str));
+                        self.icons.save();
+                    }
+                    self.submode = Submode::List;
+                }
+                Key::Esc => self.submode = Submode::List,
+                _ => (),
+            },
+            Submode::Rename { position, name } => match key {
+                key if keymap::is_enter(key) => {
+                    let (position, name) = (*position, std::mem::take(name));
+                    self.submode = Submode::List;
+                    self.rename(position, &name);
+                }
+                Key::Esc => self.submode = Submode::List,
+                Key::Backspace => {
+                    name.pop();
+                }
+                Key::Char('\t') => {
+                    *name = free_name(&self.tabs, *position, name);
+                }
+                Key::Char(c) if !c.is_control() => name.push(c),
+                _ => (),
+            },
+            Submode::Global { selected } => {
+                let selected = *selected;
+                self.update_global(key, selected);
+            }
+            Submode::Panes { selected } => match key {
+                key if self.config.keymap.down(key) => *selected += 1,
+                key if self.config.keymap.up(key) => *selected = selected.wrapping_sub(1),
+                key if keymap::is_enter(key) => {
+                    let selected = *selected;
+                    if let Some((_, pane)) = self.pane_entries().get(selected) {
+                        close();
+                        log::action(&format!("focus pane={} plugin={}", pane.id, pane.is_plugin));
+                        if pane.is_plugin {
+                            host::focus_plugin_pane(pane.id, false);
+                        } else {
+                            host::focus_terminal_pane(pane.id, false);
+                        }
+                    }
+                }
+                Key::Esc => self.submode = Submode::List,
+                key if keymap::matches(self.config.keymap.panes, key) => {
+                    self.submode = Submode::List
+                }
+                key if keymap::matches(self.config.keymap.plugin_panes, key) => {
+                    self.show_plugin_panes = !self.show_plugin_panes;
+                    *selected = 0;
+                }
+                key if keymap::matches(self.config.keymap.pane_kind, key) => {
+                    self.pane_kind = self.pane_kind.next();
+                    *selected = 0;
+                }
+                Key::Backspace => {
+                    self.filter.pop();
+                    *selected = 0;
+                }
+                Key::Char(c) if c.is_ascii() && !c.is_control() => {
+                    self.filter.push(c);
+                    *selected = 0;
+                }
+                _ => (),
+            },
+            Submode::Tree { selected, .. } => {
+                let selected = *selected;
+                self.update_tree(key, selected);
+            }
+            Submode::Projects { selected } => match key {
+                key if self.config.keymap.down(key) => *selected += 1,
+                key if self.config.keymap.up(key) => *selected = selected.wrapping_sub(1),
+                key if keymap::is_enter(key) => {
+                    let selected = *selected;
+                    if let Some(project) = self.projects().get(selected) {
+                        close();
+                        // a project opened before is only switched to
+                        if self.tabs.iter().any(|tab| tab.name == project.name) {
+                            host::go_to_tab_name(&project.name);
+                        } else {
+                            project.open();
+                        }
+                    }
+                }
+                Key::Esc => self.submode = Submode::List,
+                key if keymap::matches(self.config.keymap.projects, key) => {
+                    self.submode = Submode::List
+                }
+                Key::Backspace => {
+                    self.filter.pop();
+                    *selected = 0;
+                }
+                Key::Char(c) if c.is_ascii() && !c.is_control() => {
+                    self.filter.push(c);
+                    *selected = 0;
+                }
+                _ => (),
+            },
+            Submode::Palette { selected } => match key {
+                key if self.config.keymap.down(key) => *selected += 1,
+                key if self.config.keymap.up(key) => *selected = selected.wrapping_sub(1),
+                key if keymap::is_enter(key) => {
+                    let selected = *selected;
+                    if let Some(action) = self.palette_actions().get(selected) {
+                        action.run(&host::get_plugin_ids().initial_cwd);
+                    }
+                }
+                Key::Esc => self.submode = Submode::List,
+                key if keymap::matches(self.config.keymap.palette, key) => {
+                    self.submode = Submode::List
+                }
+                Key::Backspace => {
+                    self.filter.pop();
+                    *selected = 0;
+                }
+                Key::Char(c) if c.is_ascii() && !c.is_control() => {
+                    self.filter.push(c);
+                    *selected = 0;
+                }
+                _ => (),
+            },
+            Submode::Cheatsheet => match key {
+                Key::Esc => self.submode = Submode::List,
+                key if keymap::matches(self.config.keymap.cheatsheet, key) => {
+                    self.submode = Submode::List
+                }
+                key if keymap::matches(self.config.keymap.snippet, key) => {
+                    self.submode = Submode::Snippet
+                }
+                Key::Backspace => {
+                    self.filter.pop();
+                }
+                Key::Char(c) if c.is_ascii() && !c.is_control() => self.filter.push(c),
+                _ => (),
+            },
+            Submode::Snippet => match key {
+                key if key == Key::Esc || keymap::matches(self.config.keymap.snippet, key) => {
+                    self.submode = Submode::Cheatsheet
+                }
+                _ => (),
+            },
+            Submode::Stats => match key {
+                Key::Esc => self.submode = Submode::List,
+                key if keymap::matches(self.config.keymap.stats, key) => {
+                    self.submode = Submode::List
+                }
+                _ => (),
+            },
+            Submode::Permissions => match key {
+                // zellij asks again for whatever wasn't granted
+                key if keymap::is_enter(key) => {
+                    self.permissions = None;
+                    host::request_permission(&self.needed_permissions());
+                }
+                Key::Esc => self.submode = Submode::List,
+                key if keymap::matches(self.config.keymap.permissions, key) => {
+                    self.submode = Submode::List
+                }
+                _ => (),
+            },
+            Submode::Confirm(_) => match confirm::answer(key) {
+                Some(true) => {
+                    if let Submode::Confirm(confirm) = std::mem::take(&mut self.submode) {
+                        self.run_destructive(confirm.action);
+                    }
+                }
+                Some(false) => self.submode = Submode::List,
+                None => (),
+            },
+        }
+    }
+
+    fn render_global(&self, frame: &mut Frame, selected: usize, rows: usize, cols: usize) {
+        let prompt_cols = cols.saturating_sub(self.prompt_width() + 1);
+        let prompt = if self.filter.is_empty() {
+            width::truncate("(filter the tabs of other sessions)", prompt_cols)
+        } else {
+            width::truncate(&self.filter, prompt_cols)
+        };
+        writeln!(frame, "{} {}", self.prompt(), prompt.dimmed().italic());
+
+        let lines = self.global_lines();
+        if lines.is_empty() {
+            let message = if !self.sessions_received {
+                "waiting for sessions..."
+            } else if self.sessions.is_empty() {
+                "no other sessions"
+            } else {
+                "no tabs of other sessions match"
+            };
+            writeln!(
+                frame,
+                "{}",
+                width::truncate(message, cols).dimmed().italic()
+            );
+            return;
+        }
+
+        // scrolled just far enough to show the selection
+        let rows = rows.saturating_sub(1).max(1);
+        let start = (selected + 1).saturating_sub(rows);
+        for (index, line) in lines.iter().enumerate().skip(start).take(rows) {
+            let text = match line {
+                GlobalLine::Session {
+                    name,
+                    tabs,
+                    collapsed,
+                } => {
+                    let arrow = if *collapsed { "▸" } else { "▾" };
+                    match tabs {
+                        1 => format!("{} {} (1 tab)", arrow, name),
+                        tabs => format!("{} {} ({} tabs)", arrow, name, tabs),
+                    }
+                }
+                GlobalLine::Tab { tab, .. } => format!("  {}:{}", tab.position + 1, tab.name),
+            };
+            if index == selected {
+                writeln!(frame, "{}", self.highlight(&width::fit(&text, cols)));
+            } else if matches!(line, GlobalLine::Session { .. }) {
+                writeln!(frame, "{}", width::truncate(&text, cols).bold());
+            } else {
+                writeln!(frame, "{}", width::truncate(&text, cols));
+            }
+        }
+    }
+
+    fn render_palette(&self, frame: &mut Frame, selected: usize, rows: usize, cols: usize) {
+        let prompt_cols = cols.saturating_sub(self.prompt_width() + 1);
+        let prompt = if self.filter.is_empty() {
+            width::truncate("(filter zellij actions)", prompt_cols)
+        } else {
+            width::truncate(&self.filter, prompt_cols)
+        };
+        writeln!(frame, "{} {}", self.prompt(), prompt.dimmed().italic());
+
+        let actions = self.palette_actions();
+        if actions.is_empty() {
+            writeln!(
+                frame,
+                "{}",
+                width::truncate("no actions match", cols).dimmed().italic()
+            );
+            return;
+        }
+
+        // scrolled just far enough to show the selection
+        let rows = rows.saturating_sub(1).max(1);
+        let start = (selected + 1).saturating_sub(rows);
+        for (index, action) in actions.iter().enumerate().skip(start).take(rows) {
+            if index == selected {
+                writeln!(
+                    frame,
+                    "{}",
+                    self.highlight(&width::fit(action.label(), cols))
+                );
+            } else {
+                writeln!(frame, "{}", width::truncate(action.label(), cols));
+            }
+        }
+    }
+
+    fn render_panes(&self, frame: &mut Frame, selected: usize, rows: usize, cols: usize) {
+        let prompt_cols = cols.saturating_sub(self.prompt_width() + 1);
+        let prompt = if self.filter.is_empty() {
+            let placeholder = format!(
+                "(filter {}, {} {} plugin panes, {} for floating or tiled only)",
+                self.pane_kind.label(),
+                self.config.keymap.plugin_panes,
+                if self.show_plugin_panes {
+                    "hides"
+                } else {
+                    "shows"
+                },
+                self.config.keymap.pane_kind
+            );
+            width::truncate(&placeholder, prompt_cols)
+        } else {
+            width::truncate(&self.filter, prompt_cols)
+        };
+        writeln!(frame, "{} {}", self.prompt(), prompt.dimmed().italic());
+
+        let panes = self.pane_entries();
+        if panes.is_empty() {
+            writeln!(
+                frame,
+                "{}",
+                width::truncate(&format!("no {} match", self.pane_kind.label()), cols)
+                    .dimmed()
+                    .italic()
+            );
+            return;
+        }
+
+        // scrolled just far enough to show the selection
+        let rows = rows.saturating_sub(1).max(1);
+        let start = (selected + 1).saturating_sub(rows);
+        for (index, (tab, pane)) in panes.iter().enumerate().skip(start).take(rows) {
+            let name = format!("{}:{} ▸ {}", tab.position + 1, tab.name, pane.title);
+            let text = format!("{}{}", name, pane_tags(pane));
+            if index == selected {
+                writeln!(frame, "{}", self.highlight(&width::fit(&text, cols)));
+            } else {
+                let text = width::truncate(&text, cols);
+                let tags = text.strip_prefix(name.as_str()).unwrap_or_default();
+                let name = &text[..text.len() - tags.len()];
+                writeln!(frame, "{}{}", name, tags.dimmed());
+            }
+        }
+    }
+
+    fn render_tree(&self, frame: &mut Frame, selected: usize, rows: usize, cols: usize) {
+        let prompt_cols = cols.saturating_sub(self.prompt_width() + 1);
+        let prompt = if self.filter.is_empty() {
+            width::truncate(
+                "(filter tabs and panes, Right expands, Left collapses)",
+                prompt_cols,
+            )
+        } else {
+            width::truncate(&self.filter, prompt_cols)
+        };
+        writeln!(frame, "{} {}", self.prompt(), prompt.dimmed().italic());
+
+        let nodes = self.tree_nodes();
+        if nodes.is_empty() {
+            writeln!(
+                frame,
+                "{}",
+                width::truncate("no tabs or panes match", cols)
+                    .dimmed()
+                    .italic()
+            );
+            return;
+        }
+
+        // scrolled just far enough to show the selection
+        let rows = rows.saturating_sub(1).max(1);
+        let start = (selected + 1).saturating_sub(rows);
+        for (index, node) in nodes.iter().enumerate().skip(start).take(rows) {
+            let (text, details) = match node {
+                Node::Tab {
+                    tab,
+                    expanded,
+                    panes,
+                } => {
+                    let arrow = if *expanded { "▾" } else { "▸" };
+                    let text = format!("{} {}:{}", arrow, tab.position + 1, tab.name);
+                    let details = match panes {
+                        1 => " (1 pane)".to_string(),
+                        panes => format!(" ({} panes)", panes),
+                    };
+                    (text, details)
+                }
+                Node::Pane { pane, last, .. } => {
+                    let branch = if *last { "└" } else { "├" };
+                    (format!("  {} {}", branch, pane.title), pane_tags(pane))
+                }
+            };
+            let line = format!("{}{}", text, details);
+            if index == selected {
+                writeln!(frame, "{}", self.highlight(&width::fit(&line, cols)));
+            } else {
+                let line = width::truncate(&line, cols);
+                let details = line.strip_prefix(text.as_str()).unwrap_or_default();
+                let text = &line[..line.len() - details.len()];
+                writeln!(frame, "{}{}", text, details.dimmed());
+            }
+        }
+    }
+
+    fn render_projects(&self, frame: &mut Frame, selected: usize, rows: usize, cols: usize) {
+        let prompt_cols = cols.saturating_sub(self.prompt_width() + 1);
+        let prompt = if self.filter.is_empty() {
+            width::truncate("(filter projects)", prompt_cols)
+        } else {
+            width::truncate(&self.filter, prompt_cols)
+        };
+        writeln!(frame, "{} {}", self.prompt(), prompt.dimmed().italic());
+
+        let projects = self.projects();
+        if projects.is_empty() {
+            let message = if self.config.projects.is_empty() {
+                "no projects, add some with project_<name> options"
+            } else {
+                "no projects match"
+            };
+            writeln!(
+                frame,
+                "{}",
+                width::truncate(message, cols).dimmed().italic()
+            );
+            return;
+        }
+
+        // scrolled just far enough to show the selection
+        let rows = rows.saturating_sub(1).max(1);
+        let start = (selected + 1).saturating_sub(rows);
+        for (index, project) in projects.iter().enumerate().skip(start).take(rows) {
+            let text = format!("{}  {}", project.name, project.description());
+            if index == selected {
+                writeln!(frame, "{}", self.highlight(&width::fit(&text, cols)));
+            } else {
+                let text = width::truncate(&text, cols);
+                // whatever survived the truncation past the name is the description
+                let description = text.strip_prefix(project.name.as_str()).unwrap_or_default();
+                let name = &text[..text.len() - description.len()];
+                writeln!(frame, "{}{}", name, description.dimmed());
+            }
+        }
+    }
+
+    fn render_snippet(&self, frame: &mut Frame, cols: usize) {
+        let title = "paste this in a mode of zellij's keybinds block";
+        writeln!(
+            frame,
+            "{} {}",
+            self.prompt(),
+            width::truncate(title, cols.saturating_sub(self.prompt_width() + 1)).dimmed()
+        );
+        for line in snippet::lines(&self.configuration) {
+            writeln!(frame, "{}", width::truncate(&line, cols));
+        }
+    }
+
+    fn render_cheatsheet(&self, frame: &mut Frame, rows: usize, cols: usize) {
+        let prompt_cols = cols.saturating_sub(self.prompt_width() + 1);
+        let prompt = if self.filter.is_empty() {
+            let placeholder = format!(
+                "(search zellij's keybindings, {} for one launching room)",
+                self.config.keymap.snippet
+            );
+            width::truncate(&placeholder, prompt_cols)
+        } else {
+            width::truncate(&self.filter, prompt_cols)
+        };
+        writeln!(frame, "{} {}", self.prompt(), prompt.dimmed().italic());
+
+        let filter = self.filter.to_lowercase();
+        let mut bindings: Vec<_> = self
+            .bindings
+            .iter()
+            .filter_map(|binding| {
+                let score = self.config.matcher.score(&binding.search_str(), &filter)?;
+                Some((binding, score))
+            })
+            .collect();
+        bindings.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+
+        if bindings.is_empty() {
+            writeln!(
+                frame,
+                "{}",
+                width::truncate("no keybindings match", cols)
+                    .dimmed()
+                    .italic()
+            );
+            return;
+        }
+
+        // the prompt takes the first row
+        for (binding, _) in bindings.iter().take(rows.saturating_sub(1)) {
+            let keys = format!("{:?}: {} ", binding.mode, binding.keys);
+            let actions =
+                width::truncate(&binding.actions, cols.saturating_sub(width::width(&keys)));
+            writeln!(
+                frame,
+                "{}{}",
+                width::truncate(&keys, cols).bold(),
+                actions.dimmed()
+            );
+        }
+    }
+
+    fn render_config_errors(&self, frame: &mut Frame, rows: usize, cols: usize) {
+        let title = "room's configuration has problems, using the defaults instead:";
+        if self.config.a11y {
+            writeln!(frame, "{}", title);
+            for error in &self.config_errors {
+                writeln!(frame, "configuration error: {}", error);
+            }
+            writeln!(frame, "press any key to continue");
+            return;
+        }
+
+        writeln!(
+            frame,
+            "{} {}",
+            self.prompt(),
+            self.theme
+                .paint(
+                    &width::truncate(title, cols.saturating_sub(self.prompt_width() + 1)),
+                    Role::Warning
+                )
+                .bold()
+        );
+        // the title and the hint take a row each
+        for error in self.config_errors.iter().take(rows.saturating_sub(2)) {
+            writeln!(frame, "{}", width::truncate(&format!("  {}", error), cols));
+        }
+        writeln!(
+            frame,
+            "{}",
+            width::truncate("(press any key to continue)", cols).dimmed()
+        );
+    }
+
+    fn render_onboarding(&self, frame: &mut Frame, rows: usize, cols: usize) {
+        let lines = onboarding::lines(&self.config.keymap);
+        if self.config.a11y {
+            for line in lines.iter().filter(|line| !line.is_empty()) {
+                writeln!(frame, "{}", line.trim());
+            }
+            writeln!(frame, "press any key to start");
+            return;
+        }
+
+        // the hint takes the last row
+        for line in lines.iter().take(rows.saturating_sub(1)) {
+            writeln!(frame, "{}", width::truncate(line, cols));
+        }
+        writeln!(
+            frame,
+            "{}",
+            width::truncate("(press any key to start)", cols).dimmed()
+        );
+    }
+
+    /// Zellij only answers for all the permissions at once, so they all share its answer.
+    fn permission_statuses(&self) -> Vec<(PermissionType, &'static str)> {
+        let status = match self.permissions {
+            Some(PermissionStatus::Granted) => "granted",
+            Some(PermissionStatus::Denied) => "denied",
+            None => "waiting for an answer",
+        };
+        self.needed_permissions()
+            .into_iter()
+            .map(|permission| (permission, status))
+            .collect()
+    }
+
+    fn render_permissions(&self, frame: &mut Frame, cols: usize) {
+        writeln!(
+            frame,
+            "{} {}",
+            self.prompt(),
+            width::truncate(
+                "permissions (Enter to ask for them again)",
+                cols.saturating_sub(self.prompt_width() + 1)
+            )
+        );
+
+        for (permission, status) in self.permission_statuses() {
+            let status = match self.permissions {
+                Some(PermissionStatus::Granted) => self.theme.paint(status, Role::Toast),
+                Some(PermissionStatus::Denied) => self.theme.paint(status, Role::Error),
+                None => status.dimmed().to_string(),
+            };
+            let text = format!(
+                "{} ({}) ",
+                permission.display_name(),
+                permissions::purpose(permission)
+            );
+            // the status is short, it's the description that gets cut
+            let text = width::truncate(&text, cols.saturating_sub(width::visible_width(&status)));
+            writeln!(frame, "{}{}", text, status);
+        }
+    }
+
+    fn render_icons(
+        &self,
+        frame: &mut Frame,
+        position: usize,
+        selected: usize,
+        rows: usize,
+        cols: usize,
+    ) {
+        let title = format!("icon for {}", position + 1);
+        writeln!(
+            frame,
+            "{} {}",
+            self.prompt(),
+            width::truncate(&title, cols.saturating_sub(self.prompt_width() + 1))
+        );
+
+        let choices = std::iter::once("none").chain(self.config.icons.iter().map(String::as_str));
+        // scrolled just far enough to show the selection
+        let rows = rows.saturating_sub(1).max(1);
+        let start = (selected + 1).saturating_sub(rows);
+        for (index, choice) in choices.enumerate().skip(start).take(rows) {
+            if index == selected {
+                writeln!(frame, "{}", self.highlight(&width::fit(choice, cols)));
+            } else {
+                writeln!(frame, "{}", width::truncate(choice, cols));
+            }
+        }
+    }
+
+    fn render_stats(&self, frame: &mut Frame, cols: usize) {
+        writeln!(
+            frame,
+            "{} {}",
+            self.prompt(),
+            width::truncate(
+                "tab usage, most used first",
+                cols.saturating_sub(self.prompt_width() + 1)
+            )
+        );
+
+        let mut tabs: Vec<_> = self
+            .tabs
+            .iter()
+            .map(|tab| (tab, self.stats.get(&tab.name)))
+            .collect();
+        tabs.sort_by_key(|(_, stats)| std::cmp::Reverse((stats.visits, stats.focus_secs)));
+
+        for (tab, stats) in tabs {
+            let visits = match stats.visits {
+                1 => "1 visit".to_string(),
+                visits => format!("{} visits", visits),
+            };
+            let mut text = format!(
+                "{}:{}  {}, {} through room, {} focused",
+                tab.position + 1,
+                tab.name,
+                visits,
+                stats.switches,
+                stats::format_duration(stats.focus_secs)
+            );
+            if let Some(stamp) = self.last_active_stamp(tab) {
+                text.push_str(&format!(", last {}", stamp));
+            }
+            writeln!(frame, "{}", width::truncate(&text, cols));
+        }
+    }
+
+    /// Renders for screen readers: no colors or padding, every line says what it is and the
+    /// selected item says so first. Lines aren't truncated either, the terminal wraps them.
+    fn render_plain(&self, frame: &mut Frame, rows: usize) {
+        let mut lines = Vec::new();
+        let item = |selected: bool, text: String| {
+            if selected {
+                format!("selected: {}", text)
+            } else {
+                text
+            }
+        };
+
+        if let Some(tab) = self
+            .toast
+            .and_then(|position| self.tabs.iter().find(|tab| tab.position == position))
+        {
+            writeln!(frame, "switched to {}: {}", tab.position + 1, tab.name);
+            return;
+        }
+
+        match &self.submode {
+            Submode::Actions(menu) => {
+                if let Some(tab) = self.tabs.iter().find(|tab| tab.position == menu.position) {
+                    lines.push(format!("actions for {}: {}", tab.position + 1, tab.name));
+                    for (index, action) in TabAction::ALL.iter().enumerate() {
+                        let on = match action {
+                            TabAction::Pin => self.pins.contains(&tab.name),
+                            TabAction::Protect => self.protected.contains(&tab.name),
+                            _ => false,
+                        };
+                        lines.push(item(index == menu.selected, action.label(on).to_string()));
+                    }
+                }
+            }
+            Submode::Icon { position, selected } => {
+                lines.push(format!("icon for tab {}", position + 1));
+                lines.push(item(*selected == 0, "none".to_string()));
+                for (index, icon) in self.config.icons.iter().enumerate() {
+                    lines.push(item(*selected == index + 1, icon.clone()));
+                }
+            }
+            Submode::Rename { position, name } => {
+                lines.push(format!("rename {} to: {}", position + 1, name));
+                if name_taken(&self.tabs, *position, name) {
+                    lines.push(format!(
+                        "another tab is named {}, Tab renames to {} instead",
+                        name,
+                        free_name(&self.tabs, *position, name)
+                    ));
+                }
+                lines.push("Enter to confirm, Esc to cancel".to_string());
+            }
+            Submode::Confirm(confirm) => {
+                lines.push(format!("confirm: {} {}", confirm.question, confirm::HINT));
+            }
+            Submode::Global { selected } => {
+                lines.push(format!("filter other sessions: {}", self.filter));
+                for (index, line) in self.global_lines().iter().enumerate() {
+                    let text = match line {
+                        GlobalLine::Session {
+                            name,
+                            tabs,
+                            collapsed,
+                        } => format!(
+                            "session {}, {} matching tabs, {}",
+                            name,
+                            tabs,
+                            if *collapsed { "collapsed" } else { "expanded" }
+                        ),
+                        GlobalLine::Tab { session, tab } => format!(
+                            "tab {}: {}, in session {}",
+                            tab.position + 1,
+                            tab.name,
+                            session
+                        ),
+                    };
+                    lines.push(item(index == *selected, text));
+                }
+            }
+            Submode::Palette { selected } => {
+                lines.push(format!("filter actions: {}", self.filter));
+                for (index, action) in self.palette_actions().iter().enumerate() {
+                    lines.push(item(index == *selected, action.label().to_string()));
+                }
+            }
+            Submode::Panes { selected } => {
+                lines.push(format!(
+                    "filter {}: {}",
+                    self.pane_kind.label(),
+                    self.filter
+                ));
+                for (index, (tab, pane)) in self.pane_entries().iter().enumerate() {
+                    let text = format!(
+                        "tab {}: {}, pane {}{}",
+                        tab.position + 1,
+                        tab.name,
+                        pane.title,
+                        pane_tags(pane)
+                    );
+                    lines.push(item(index == *selected, text));
+                }
+            }
+            Submode::Tree { selected, .. } => {
+                lines.push(format!("filter tabs and panes: {}", self.filter));
+                for (index, node) in self.tree_nodes().iter().enumerate() {
+                    let text = match node {
+                        Node::Tab {
+                            tab,
+                            expanded,
+                            panes,
+                        } => format!(
+                            "tab {}: {}, {} panes, {}",
+                            tab.position + 1,
+                            tab.name,
+                            panes,
+                            if *expanded { "expanded" } else { "collapsed" }
+                        ),
+                        Node::Pane { tab, pane, .. } => format!(
+                            "pane {}{}, in tab {}",
+                            pane.title,
+                            pane_tags(pane),
+                            tab.position + 1
+                        ),
+                    };
+                    lines.push(item(index == *selected, text));
+                }
+            }
+            Submode::Projects { selected } => {
+                lines.push(format!("filter projects: {}", self.filter));
+                for (index, project) in self.projects().iter().enumerate() {
+                    let text = format!("{}, {}", project.name, project.description());
+                    lines.push(item(index == *selected, text));
+                }
+            }
+            Submode::Cheatsheet => {
+                lines.push(format!("search keybindings: {}", self.filter));
+                let filter = self.filter.to_lowercase();
+                for binding in &self.bindings {
+                    if self
+                        .config
+                        .matcher
+                        .score(&binding.search_str(), &filter)
+                        .is_some()
+                    {
+                        lines.push(format!(
+                            "{:?} mode, {}: {}",
+                            binding.mode, binding.keys, binding.actions
+                        ));
+                    }
+                }
+            }
+            Submode::Snippet => {
+                lines.push(
+                    "a keybinding launching room as configured, for zellij's config".to_string(),
+                );
+                lines.extend(snippet::lines(&self.configuration));
+            }
+            Submode::Permissions => {
+                lines.push("permissions, Enter asks for them again".to_string());
+                for (permission, status) in self.permission_statuses() {
+                    lines.push(format!(
+                        "{}, for {}: {}",
+                        permission.display_name(),
+                        permissions::purpose(permission),
+                        status
+                    ));
+                }
+            }
+            Submode::Stats => {
+                lines.push("tab usage".to_string());
+                for tab in &self.tabs {
+                    let stats = self.stats.get(&tab.name);
+                    let mut text = format!(
+                        "{}: {}, {} visits, {} through room, {} focused",
+                        tab.position + 1,
+                        tab.name,
+                        stats.visits,
+                        stats.switches,
+                        stats::format_duration(stats.focus_secs)
+                    );
+                    if let Some(stamp) = self.last_active_stamp(tab) {
+                        text.push_str(&format!(", last active {}", stamp));
+                    }
+                    lines.push(text);
+                }
+            }
+            Submode::List => {
+                lines.push(format!("filter: {}", self.filter));
+
+                let recent = self.recent_count();
+                for (index, tab) in self.first_listed(rows).into_iter().enumerate() {
+                    let mut text = format!("{}: {}", tab.position + 1, tab.name);
+                    if index < recent {
+                        text.push_str(", recent");
+                    }
+                    if let Some(number) = self.duplicate_number(tab) {
+                        text.push_str(&format!(", duplicate {}", number));
+                    }
+                    if tab.active {
+                        text.push_str(", active");
+                    }
+                    if self.pins.contains(&tab.name) {
+                        text.push_str(", pinned");
+                    }
+                    if self.marked.contains(&tab.name) {
+                        text.push_str(", marked");
+                    }
+                    if let Some(color) = self.colors.get(&tab.name) {
+                        text.push_str(&format!(", {}", color));
+                    }
+                    if let Some(stamp) = self.last_active_stamp(tab) {
+                        text.push_str(&format!(", active {}", stamp));
+                    }
+                    match self.failed_panes(tab.position) {
+                        0 => (),
+                        _ if !self.config.show_exit_status => (),
+                        1 => text.push_str(", a command failed"),
+                        failed => text.push_str(&format!(", {} commands failed", failed)),
+                    }
+                    text.push_str(&self.metadata(tab));
+                    lines.push(item(Some(tab.position) == self.selected, text));
+                }
+                if lines.len() == 1 && !self.filter.is_empty() {
+                    lines.push(self.config.empty_message.replace("{query}", &self.filter));
+                }
+            }
+        }
+
+        lines.truncate(rows.max(1));
+        writeln!(frame, "{}", lines.join("\n"));
+    }
+
+    /// Selects the next tab, the first one after the last.
+    fn select_down(&mut self) {
+        let index = match self.selected_index() {
+            (_, 0) => return,
+            (Some(index), count) if index + 1 < count => index + 1,
+            _ => 0,
+        };
+        self.selected = self.nth_listed(index).map(|tab| tab.position);
+    }
+
+    /// Selects the previous tab, the last one before the first.
+    fn select_up(&mut self) {
+        let index = match self.selected_index() {
+            (_, 0) => return,
+            (Some(index), _) if index > 0 => index - 1,
+            (_, count) => count - 1,
+        };
+        self.selected = self.nth_listed(index).map(|tab| tab.position);
+    }
+
+    /// Moves the selection `by` tabs down (up when negative), stopping at the ends of the list
+    /// rather than wrapping around like a single step does.
+    fn select_by(&mut self, by: isize) {
+        let (current, count) = self.selected_index();
+        let Some(last) = count.checked_sub(1) else {
+            return;
+        };
+        let index = current
+            .unwrap_or_default()
+            .saturating_add_signed(by)
+            .min(last);
+        self.selected = self.nth_listed(index).map(|tab| tab.position);
+    }
+
+    /// How many tabs a page is, the ones the last frame showed.
+    fn page(&self) -> isize {
+        let tab_height = if self.detailed { 2 } else { 1 };
+        (self.tab_lines.len() / tab_height).max(1) as isize
+    }
+
+    /// Starts the pulse when a jump moved the selection and `animate_selection` is on.
+    fn pulse_from(&mut self, selected: Option<usize>) {
+        if self.config.animate_selection && self.selected != selected && self.pulse == 0 {
+            self.pulse = PULSE_STEPS;
+            host::set_timeout(PULSE_STEP_SECS);
+        }
+    }
+
+    /// The tab drawn on `line` of the pane, the first line being the prompt.
+    fn tab_at_line(&self, line: isize) -> Option<usize> {
+        let line = usize::try_from(line).ok()?.checked_sub(self.frame_top)?;
+        self.tab_lines
+            .iter()
+            .find(|(tab_line, _)| *tab_line == line)
+            .map(|(_, position)| *position)
+    }
+    /// The name of what's being listed, the overlays opened on a tab still list tabs.
+    fn mode_name(&self) -> &'static str {
+        match self.submode {
+            Submode::Global { .. } => "sessions",
+            Submode::Palette { .. } => "actions",
+            Submode::Cheatsheet => "keys",
+            Submode::Snippet => "snippet",
+            Submode::Panes { .. } => "panes",
+            Submode::Tree { .. } => "tree",
+            Submode::Projects { .. } => "projects",
+            _ => "tabs",
+        }
+    }
+
+    /// Where a nested mode was opened from, `tabs ▸ rename "api"`, since Esc goes back there.
+    fn breadcrumb(&self) -> Option<String> {
+        let tab_name = |position: usize| {
+            self.tabs
+                .iter()
+                .find(|tab| tab.position == position)
+                .map_or(String::new(), |tab| format!(" \"{}\"", tab.name))
+        };
+
+        let crumb = match &self.submode {
+            Submode::Actions(menu) => format!("actions{}", tab_name(menu.position)),
+            Submode::Rename { position, .. } => format!("rename{}", tab_name(*position)),
+            Submode::Icon { position, .. } => format!("icon{}", tab_name(*position)),
+            Submode::Confirm(confirm) => match &confirm.action {
+                Destructive::CloseTabs(positions) if positions.len() == 1 => {
+                    format!("close{}", tab_name(positions[0]))
+                }
+                Destructive::CloseTabs(positions) => format!("close {} tabs", positions.len()),
+            },
+            _ => return None,
+        };
+        Some(format!("{} ▸ {}", self.mode_name(), crumb))
+    }
+
+    /// The mode a snapshot's badge names, the ones not worth coming back to are left alone.
+    fn restore_mode(&mut self, name: &str) {
+        self.submode = match name {
+            "sessions" => Submode::Global { selected: 0 },
+            "actions" => Submode::Palette { selected: 0 },
+            "keys" => Submode::Cheatsheet,
+            "snippet" => Submode::Snippet,
+            "panes" => Submode::Panes { selected: 0 },
+            "tree" => Submode::Tree {
+                selected: 0,
+                expanded: BTreeMap::new(),
+            },
+            "projects" => Submode::Projects { selected: 0 },
+            _ => Submode::List,
+        };
+    }
+
+    /// Listens to session updates while global mode is open, and only then. They list the tabs
+    /// and panes of every session, which is a lot to go through on every change with many
+    /// sessions around when all that's wanted are this session's tabs.
+    fn follow_sessions(&mut self) {
+        let wanted = matches!(self.submode, Submode::Global { .. });
+        if wanted == self.following_sessions {
+            return;
+        }
+
+        if wanted {
+            host::subscribe(&[EventType::SessionUpdate]);
+        } else {
+            host::unsubscribe(&[EventType::SessionUpdate]);
+            self.sessions.clear();
+            self.sessions_received = false;
+        }
+        self.following_sessions = wanted;
+    }
+
+    /// Writes the files saved since the last flush, if that was `FLUSH_SECS` ago or `now` is
+    /// asked for. Otherwise a timer is started for the rest of the wait, so the files get written
+    /// even when no other event comes.
+    fn flush_writes(&mut self, now: bool) {
+        let time = now_secs();
+        let due = self.flushed_at + FLUSH_SECS;
+        if now || time >= due {
+            worker::flush();
+            self.flushed_at = time;
+        } else if worker::pending() && !self.flush_timer {
+            host::set_timeout(due - time);
+            self.flush_timer = true;
+        }
+    }
+
+    fn save_snapshot(&self) {
+        let selected = self
+            .tabs
+            .iter()
+            .find(|tab| Some(tab.position) == self.selected)
+            .map(|tab| tab.name.clone());
+
+        Snapshot {
+            sort: self.sort,
+            mode: self.mode_name().to_string(),
+            selected,
+            filter: self.filter.clone(),
+        }
+        .save();
+    }
+
+    /// Goes from the tabs to the other sessions, the palette, the cheatsheet, the panes, the
+    /// tree, the projects (when there are any) and back.
+    fn cycle_mode(&mut self) {
+        self.submode = match self.submode {
+            Submode::Global { .. } => Submode::Palette { selected: 0 },
+            Submode::Palette { .. } => Submode::Cheatsheet,
+            Submode::Cheatsheet => Submode::Panes { selected: 0 },
+            Submode::Panes { .. } => Submode::Tree {
+                selected: 0,
+                expanded: BTreeMap::new(),
+            },
+            Submode::Tree { .. } if !self.config.projects.is_empty() => {
+                Submode::Projects { selected: 0 }
+            }
+            Submode::Tree { .. } | Submode::Projects { .. } => Submode::List,
+            _ => Submode::Global { selected: 0 },
+        };
+    }
+
+    /// What starts the first line: the mode's badge and the key cycling modes, then the
+    /// session's name, each when asked for.
+    fn prompt(&self) -> String {
+        let mut prompt = String::new();
+        if self.config.show_mode {
+            let role = match self.submode {
+                Submode::Global { .. } => Role::GlobalBadge,
+                Submode::Palette { .. } => Role::PaletteBadge,
+                Submode::Cheatsheet | Submode::Snippet => Role::HelpBadge,
+                Submode::Panes { .. } | Submode::Tree { .. } => Role::PanesBadge,
+                Submode::Projects { .. } => Role::ProjectsBadge,
+                _ => Role::ListBadge,
+            };
+            let badge = self.theme.fill(&format!(" {} ", self.mode_name()), role);
+            prompt.push_str(&format!(
+                "{} {} ",
+                badge,
+                self.config.keymap.cycle_mode.dimmed()
+            ));
+        }
+        if let Some(session) = self
+            .session_name
+            .as_ref()
+            .filter(|_| self.config.show_session_name)
+        {
+            prompt.push_str(&format!("{} ", self.theme.paint(session, Role::Session)));
+        }
+
+        prompt.push_str(&self.theme.paint(">", Role::Prompt).bold().to_string());
+        prompt
+    }
+
+    fn prompt_width(&self) -> usize {
+        let mut prompt_width = 1;
+        if self.config.show_mode {
+            prompt_width += width::width(self.mode_name())
+                + 4
+                + width::width(&self.config.keymap.cycle_mode.to_string());
+        }
+        if let Some(session) = self
+            .session_name
+            .as_ref()
+            .filter(|_| self.config.show_session_name)
+        {
+            prompt_width += width::width(session) + 1;
+        }
+
+        prompt_width
+    }
+
+    fn render_actions(&self, frame: &mut Frame, menu: &Menu, rows: usize, cols: usize) {
+        let Some(tab) = self.tabs.iter().find(|tab| tab.position == menu.position) else {
+            return;
+        };
+
+        let title = format!("actions for {}:{}", tab.position + 1, tab.name);
+        writeln!(
+            frame,
+            "{} {}",
+            self.prompt(),
+            width::truncate(&title, cols.saturating_sub(self.prompt_width() + 1))
+        );
+
+        // scrolled just far enough to show the selection
+        let rows = rows.saturating_sub(1).max(1);
+        let start = (menu.selected + 1).saturating_sub(rows);
+        for (index, action) in TabAction::ALL.iter().enumerate().skip(start).take(rows) {
+            let on = match action {
+                TabAction::Pin => self.pins.contains(&tab.name),
+                TabAction::Protect => self.protected.contains(&tab.name),
+                _ => false,
+            };
+            let label = action.label(on);
+            if index == menu.selected {
+                writeln!(frame, "{}", self.highlight(&width::fit(label, cols)));
+            } else {
+                writeln!(frame, "{}", width::truncate(label, cols));
+            }
+        }
+    }
+
+    /// Draws the mode, with the message bar on the last row when something went wrong.
+    fn draw(&self, frame: &mut Frame, rows: usize, cols: usize) {
+        let Some(error) = &self.error else {
+            self.draw_mode(frame, rows, cols);
+            return;
+        };
+
+        // screen readers get it as one more line
+        if self.config.a11y {
+            self.draw_mode(frame, rows.saturating_sub(1), cols);
+            writeln!(frame, "error: {}, Esc dismisses", error);
+            return;
+        }
+
+        let rows = rows.saturating_sub(1);
+        self.draw_mode(frame, rows, cols);
+        frame.fit_lines(rows);
+        let bar = width::fit(&format!(" ✗ {}  (Esc dismisses)", error), cols);
+        writeln!(frame, "{}", self.theme.fill(&bar, Role::Error));
+    }
+
+    fn draw_mode(&self, frame: &mut Frame, rows: usize, cols: usize) {
+        if !self.config_errors.is_empty() {
+            self.render_config_errors(frame, rows, cols);
+            return;
+        }
+        if self.onboarding {
+            self.render_onboarding(frame, rows, cols);
+            return;
+        }
+
+        // nothing useful can be listed until zellij has sent us the tabs, which only happens
+        // once the permissions are granted
+        // the permissions panel is how to find out why the tabs don't come
+        let waiting = !self.tabs_received && !matches!(self.submode, Submode::Permissions);
+        if waiting && self.config.a11y {
+            let message = match self.permissions {
+                Some(PermissionStatus::Denied) => "permissions denied",
+                Some(PermissionStatus::Granted) => "waiting for tab info",
+                None => "waiting for permissions to be granted",
+            };
+            writeln!(frame, "{}", message);
+            return;
+        }
+        if waiting {
+            let prompt_cols = cols.saturating_sub(self.prompt_width() + 1);
+            let message = match self.permissions {
+                Some(PermissionStatus::Denied) => self.theme.paint(
+                    &width::truncate(
+                        &format!(
+                            "permissions denied, room can't list or switch tabs without them ({} \
+                             for details)",
+                            self.config.keymap.permissions
+                        ),
+                        prompt_cols,
+                    ),
+                    Role::Error,
+                ),
+                Some(PermissionStatus::Granted) => {
+                    width::truncate("waiting for tab info...", prompt_cols)
+                        .dimmed()
+                        .italic()
+                        .to_string()
+                }
+                None => width::truncate("waiting for permissions to be granted...", prompt_cols)
+                    .dimmed()
+                    .italic()
+                    .to_string(),
+            };
+
+            writeln!(frame, "{} {}", self.prompt(), message);
+            return;
+        }
+
+        if self.config.a11y {
+            self.render_plain(frame, rows);
+            return;
+        }
+
+        // room for the text after the "> " prompt
+        let prompt_cols = cols.saturating_sub(self.prompt_width() + 1);
+
+        if let Some(tab) = self
+            .toast
+            .and_then(|position| self.tabs.iter().find(|tab| tab.position == position))
+        {
+            let toast = format!("switched to {}: {}", tab.position + 1, tab.name);
+            let toast = self
+                .theme
+                .paint(&width::truncate(&toast, cols), Role::Toast);
+            writeln!(frame, "{}", toast.bold());
+            return;
+        }
+
+        let mut rows = rows;
+        if let Some(breadcrumb) = self.breadcrumb() {
+            writeln!(frame, "{}", width::truncate(&breadcrumb, cols).dimmed());
+            rows = rows.saturating_sub(1);
+        }
+
+        match &self.submode {
+            Submode::List => (),
+            Submode::Actions(menu) => {
+                self.render_actions(frame, menu, rows, cols);
+                return;
+            }
+            Submode::Confirm(_) => (),
+            Submode::Global { selected } => {
+                self.render_global(frame, *selected, rows, cols);
+                return;
+            }
+            Submode::Palette { selected } => {
+                self.render_palette(frame, *selected, rows, cols);
+                return;
+            }
+            Submode::Panes { selected } => {
+                self.render_panes(frame, *selected, rows, cols);
+                return;
+            }
+            Submode::Tree { selected, .. } => {
+                self.render_tree(frame, *selected, rows, cols);
+                return;
+            }
+            Submode::Projects { selected } => {
+                self.render_projects(frame, *selected, rows, cols);
+                return;
+            }
+            Submode::Cheatsheet => {
+                self.render_cheatsheet(frame, rows, cols);
+                return;
+            }
+            Submode::Snippet => {
+                self.render_snippet(frame, cols);
+                return;
+            }
+            Submode::Stats => {
+                self.render_stats(frame, cols);
+                return;
+            }
+            Submode::Permissions => {
+                self.render_permissions(frame, cols);
+                return;
+            }
+            Submode::Icon { position, selected } => {
+                self.render_icons(frame, *position, *selected, rows, cols);
+                return;
+            }
+            Submode::Rename { position, name } => {
+                let label = format!("rename {} to ", position + 1);
+                writeln!(
+                    frame,
+                    "{} {}{}",
+                    self.prompt(),
+                    label,
+                    width::truncate(name, prompt_cols.saturating_sub(width::width(&label)))
+                        .italic()
+                );
+                if name_taken(&self.tabs, *position, name) {
+                    let warning = format!(
+                        "another tab is named '{}', Tab renames to '{}' instead",
+                        name,
+                        free_name(&self.tabs, *position, name)
+                    );
+                    let warning = width::truncate(&warning, cols);
+                    writeln!(frame, "{}", self.theme.paint(&warning, Role::Warning));
+                } else {
+                    writeln!(
+                        frame,
+                        "{}",
+                        width::truncate("(Enter to confirm, Esc to cancel)", cols).dimmed()
+                    );
+                }
+                return;
+            }
+        }
+
+        if let Submode::Confirm(confirm) = &self.submode {
+            let question = format!("{} {}", confirm.question, confirm::HINT);
+            writeln!(
+                frame,
+                "{} {}",
+                self.prompt(),
+                self.theme
+                    .paint(&width::truncate(&question, prompt_cols), Role::Warning)
+                    .bold()
+            );
+        } else {
+            writeln!(
+                frame,
+                "{} {}",
+                self.prompt(),
+                if self.filter.is_empty() && self.sort != Sort::Score {
+                    let placeholder =
+                        format!("(filter by index or name, sorted by {})", self.sort.name());
+                    width::truncate(&placeholder, prompt_cols)
+                        .dimmed()
+                        .italic()
+                        .to_string()
+                } else if self.filter.is_empty() {
+                    width::truncate("(filter by index or name)", prompt_cols)
+                        .dimmed()
+                        .italic()
+                        .to_string()
+                } else {
+                    width::truncate(&self.filter, prompt_cols)
+                        .dimmed()
+                        .italic()
+                        .to_string()
+                }
+            );
+        }
+
+        // what's left for rows once the prompt and the hints are drawn
+        let mut list_rows = rows.saturating_sub(1);
+        if let Query::Command(line) = query::parse(&self.filter, self.config.strict_index) {
+            list_rows = list_rows.saturating_sub(1);
+            let hints = command::hints(line);
+            let hints = if hints.is_empty() {
+                "no such command".to_string()
+            } else {
+                hints.join("  ")
+            };
+            writeln!(frame, "{}", width::truncate(&hints, cols).dimmed());
+        }
+
+        let tab_height = if self.detailed { 2 } else { 1 };
+        let sections = self.sections(list_rows / tab_height);
+        if !self.filter.is_empty() && sections.is_empty() {
+            let message = self.config.empty_message.replace("{query}", &self.filter);
+            writeln!(
+                frame,
+                "{}",
+                width::truncate(&message, cols).dimmed().italic()
+            );
+            // Enter doesn't create tabs named after an index
+            if self.missing_index_error().is_none() {
+                let hint = format!("(press Enter to create a tab named '{}')", self.filter);
+                writeln!(frame, "{}", width::truncate(&hint, cols).dimmed());
+            }
+            return;
+        }
+
+        for line in sections::layout(&sections, list_rows, tab_height, self.selected) {
+            match line {
+                Line::Header(title) => {
+                    writeln!(frame, "{}", width::truncate(title, cols).dimmed().bold());
+                }
+                Line::Tab(tab) => {
+                    frame.mark_tab(tab.position);
+                    writeln!(frame, "{}", self.render_row(tab, cols));
+                    if self.detailed {
+                        frame.mark_tab(tab.position);
+                        writeln!(frame, "{}", self.render_details(tab, cols));
+                    }
+                }
+                Line::More(count) => {
+                    let more = format!("… {} more", count);
+                    writeln!(frame, "{}", width::truncate(&more, cols).dimmed());
+                }
+            }
+        }
+    }
+
+    /// `text` on the selection's background, in whichever of black or white reads best on it.
+    fn highlight(&self, text: &str) -> String {
+        let highlighted = self.theme.fill(text, Role::Highlight);
+        // every other frame of the pulse swaps the text and background colors
+        if self.pulse % 2 == 1 {
+            highlighted.bold().reversed().to_string()
+        } else {
+            highlighted.bold().to_string()
+        }
+    }
+
+    /// A tab's line in the list.
+    fn render_row(&self, tab: &TabInfo, cols: usize) -> String {
+        let pin = if self.pins.contains(&tab.name) {
+            "* "
+        } else {
+            ""
+        };
+        let pin = if self.marked.contains(&tab.name) {
+            format!("+ {}", pin)
+        } else {
+            pin.to_string()
+        };
+        let icon = self
+            .icons
+            .get(&tab.name)
+            .map_or(String::new(), |icon| format!("{} ", icon));
+        let mut text = format!("{}{}{}:{}", pin, icon, tab.position + 1, tab.name);
+        if let Some(number) = self.duplicate_number(tab) {
+            text.push_str(&format!(" ({})", number));
+        }
+        if self.sort == Sort::Panes {
+            text.push_str(&format!(" ({} panes)", self.pane_count(tab.position)));
+        }
+        if let Some(created) = self.created.get(&tab.name).filter(|_| self.sort.by_age()) {
+            let age = chrono::Utc::now().timestamp() - created;
+            text.push_str(&format!(" ({} old)", stats::format_duration(age.max(0))));
+        }
+        let selected = Some(tab.position) == self.selected;
+        let metadata = self.metadata(tab);
+        let stamp = self.last_active_stamp(tab);
+        let full_cols = cols;
+        let cols = cols.saturating_sub(stamp.as_ref().map_or(0, |stamp| width::width(stamp) + 1));
+        let mut bullet = self
+            .tab_color(&tab.name)
+            .map(|color| self.theme.accent("●", color) + " ");
+        let mut cols = cols.saturating_sub(if bullet.is_some() { 2 } else { 0 });
+        if self.config.show_exit_status && self.failed_panes(tab.position) > 0 {
+            bullet = Some(format!(
+                "{}{} ",
+                bullet.unwrap_or_default(),
+                self.theme.paint("✗", Role::Error).bold()
+            ));
+            cols = cols.saturating_sub(2);
+        }
+
+        // the selected row is padded so its background spans the whole pane
+        let full = format!("{}{}", text, metadata);
+        let full = if selected {
+            width::fit(&full, cols)
+        } else {
+            width::truncate(&full, cols)
+        };
+        // whatever survived the truncation past the name is metadata
+        let (text, metadata) = match full.strip_prefix(text.as_str()) {
+            Some(rest) => (text.clone(), rest.to_string()),
+            None => (full, String::new()),
+        };
+
+        let row = if tab.active {
+            self.theme.paint(&text, Role::Active).bold().to_string()
+        } else {
+            text
+        };
+
+        let row = if selected {
+            // a dimmed part would reset the background halfway through the row
+            self.highlight(&format!("{}{}", row, metadata))
+        } else if self.hovered == Some(tab.position) {
+            format!("{}{}", row, metadata).underline().to_string()
+        } else {
+            format!("{}{}", row, metadata.dimmed())
+        };
+        let row = format!("{}{}", bullet.unwrap_or_default(), row);
+
+        match stamp {
+            Some(stamp) => {
+                let padding = " ".repeat(
+                    full_cols.saturating_sub(width::visible_width(&row) + width::width(&stamp)),
+                );
+                let stamp = format!("{}{}", padding, stamp);
+                if selected {
+                    format!("{}{}", row, self.highlight(&stamp))
+                } else {
+                    format!("{}{}", row, stamp.dimmed())
+                }
+            }
+            None => row,
+        }
+    }
+
+    /// The second line of a tab in detailed rows: its directory, its panes and what the focused
+    /// one runs.
+    fn render_details(&self, tab: &TabInfo, cols: usize) -> String {
+        let mut details = Vec::new();
+        if let Some(cwd) = self.tab_cwds.get(tab.position).and_then(Option::as_ref) {
+            details.push(cwd.display().to_string());
+        }
+        match self.pane_count(tab.position) {
+            1 => details.push("1 pane".to_string()),
+            count => details.push(format!("{} panes", count)),
+        }
+        if let Some(command) = self
+            .focused_pane(tab.position)
+            .and_then(|pane| pane.terminal_command.as_ref())
+        {
+            details.push(format!("$ {}", command));
+        }
+
+        let details = format!("    {}", details.join(" · "));
+        width::truncate(&details, cols).dimmed().to_string()
+    }
+
+    /// When the tab was last focused, for tabs other than the active one room saw focused
+    /// before.
+    fn last_active_stamp(&self, tab: &TabInfo) -> Option<String> {
+        if tab.active {
+            return None;
+        }
+        let time = self.last_active.get(&tab.name)?;
+        self.config
+            .time_format
+            .format(time, chrono::Utc::now().timestamp())
+    }
+}
+
+/// What kind of pane `pane` is and what it runs, drawn dimmed after its title.
+fn pane_tags(pane: &PaneInfo) -> String {
+    let mut tags = String::new();
+    if let Some(command) = &pane.terminal_command {
+        tags.push_str(&format!(" $ {}", command));
+    }
+    if pane.is_floating {
+        tags.push_str(" [floating]");
+    }
+    if pane.is_plugin {
+        tags.push_str(" [plugin]");
+    }
+    tags
+}
+
+/// Whether a tab other than the one at `position` is named `name` already.
+fn name_taken(tabs: &[TabInfo], position: usize, name: &str) -> bool {
+    tabs.iter()
+        .any(|tab| tab.position != position && tab.name == name)
+}
+
+/// `name`, or `name 2`, `name 3`... when another tab than the one at `position` has it.
+fn free_name(tabs: &[TabInfo], position: usize, name: &str) -> String {
+    (1..)
+        .map(|number| match number {
+            1 => name.to_string(),
+            _ => format!("{} {}", name, number),
+        })
+        .find(|candidate| !name_taken(tabs, position, candidate))
+        .unwrap_or_default()
+}
+
+/// Closes room, flushing what was saved and logged first since nothing would flush it
+/// afterwards.
+fn close() {
+    log::action("close room");
+    worker::flush();
+    log::flush();
+    host::close_focus();
+}
+
+/// The time in the unix seconds timers are compared to.
+fn now_secs() -> f64 {
+    chrono::Utc::now().timestamp_millis() as f64 / 1000.0
+}
+
+impl ZellijPlugin for State {
+    fn load(&mut self, configuration: BTreeMap<String, String>) {
+        App::load(self, configuration);
+    }
+
+    fn update(&mut self, event: Event) -> bool {
+        App::update(self, event)
+    }
+
+    fn pipe(&mut self, message: PipeMessage) -> bool {
+        App::pipe(self, message)
+    }
+
+    fn render(&mut self, rows: usize, cols: usize) {
+        print!("{}", App::render(self, rows, cols));
+    }
+}
+
+impl App for State {
+    fn load(&mut self, configuration: BTreeMap<String, String>) {
+        let (config, errors) = Config::parse(&configuration);
+        self.config = config;
+        self.config_errors = errors;
+        if self.config.log {
+            log::enable();
+            log::action(&format!(
+                "load version={} config_errors={}",
+                env!("CARGO_PKG_VERSION"),
+                self.config_errors.len()
+            ));
+        }
+        self.configuration = configuration;
+
+        host::request_permission(&self.needed_permissions());
+
+        self.sort = self.config.sort;
+        self.show_plugin_panes = self.config.show_plugin_panes;
+        self.detailed = self.config.detailed;
+        self.theme = Theme::new(
+            self.config.theme,
+            &self.config.colors,
+            self.palette.as_ref(),
+        );
+        self.launch_cwd = host::get_plugin_ids().initial_cwd;
+        self.onboarding = !onboarding::seen();
+        if let Some(secs) = self.config.auto_close_secs {
+            self.last_input = now_secs();
+            self.idle_due = Some(self.last_input + secs);
+            host::set_timeout(secs);
+        }
+        if self.config.remember_filter {
+            self.filter = host::read_to_string(FILTER_PATH).unwrap_or_default();
+        }
+        if let Some(snapshot) = self.config.restore_state.then(Snapshot::load).flatten() {
+            self.sort = snapshot.sort;
+            self.restore_mode(&snapshot.mode);
+            self.restored_selection = snapshot.selected;
+            self.filter = snapshot.filter;
+        }
+
+        self.jumplist = JumpList::load();
+        self.stats = Stats::load();
+        self.created = Timestamps::load("/data/created");
+        self.last_active = Timestamps::load("/data/last_active");
+        self.pins = TabSet::load("/data/pins");
+        self.protected = TabSet::load("/data/protected");
+        self.colors = TabLabels::load("/data/colors");
+        self.icons = TabLabels::load("/data/icons");
+
+        host::subscribe(&[
+            EventType::TabUpdate,
+            EventType::PaneUpdate,
+            EventType::Key,
+            EventType::Mouse,
+            EventType::Timer,
+            EventType::PermissionRequestResult,
+            EventType::ModeUpdate,
+            EventType::CustomMessage,
+            EventType::RunCommandResult,
+            EventType::Visible,
+        ]);
+        self.follow_sessions();
+    }
+
+    fn update(&mut self, event: Event) -> bool {
+        let _timing = log::event(&event);
+        // the configuration's problems are up until a key says they've been read
+        if matches!(event, Event::Key(_)) && !self.config_errors.is_empty() {
+            self.config_errors.clear();
+            return true;
+        }
+        if matches!(event, Event::Key(_)) && self.onboarding {
+            self.onboarding = false;
+            onboarding::mark_seen();
+            return true;
+        }
+
+        // Esc dismisses the message before it does anything else
+        if matches!(event, Event::Key(Key::Esc)) && self.error.take().is_some() {
+            return true;
+        }
+
+        let mut should_render = false;
+
+        let hidden = matches!(event, Event::Visible(false));
+        match event {
+            Event::Key(_) | Event::Mouse(_) => self.last_input = now_secs(),
+            Event::Timer(_) => {
+                self.check_idle();
+                // it may have been the flush's, which starts another if it fired early
+                self.flush_timer = false;
+            }
+            _ => (),
+        }
+
+        match event {
+            Event::PermissionRequestResult(status) => {
+                self.permissions = Some(status);
+                should_render = true;
+            }
+            // zellij repeats the same tabs whenever anything in the session changes
+            Event::TabUpdate(tab_info) if self.tabs_received && tab_info == self.tabs => (),
+            Event::TabUpdate(tab_info) => {
+                let keep_selection = self.tabs_received
+                    && tab_info
+                        .iter()
+                        .any(|tab| Some(tab.position) == self.selected);
+
+                search::refresh(
+                    &mut self.search_strs,
+                    &tab_info,
+                    self.config.show_swap_layout,
+                    self.config.fold_accents,
+                );
+                if keep_selection {
+                    // later updates (a rename, a switch that kept us open) shouldn't lose our place
+                    self.tabs = tab_info;
+                } else if self.config.active_tab == ActiveTab::Keep && self.filter.is_empty() {
+                    self.selected = tab_info.iter().find_map(|tab| {
+                        if tab.active {
+                            Some(tab.position)
+                        } else {
+                            None
+                        }
+                    });
+                    self.tabs = tab_info;
+                } else {
+                    // the active tab isn't worth the top slot, start on the best other match
+                    self.tabs = tab_info;
+                    self.reset_selection();
+                }
+
+                // what was selected before the session went down wins over the usual pick, once
+                if let Some(name) = self.restored_selection.take() {
+                    if let Some(tab) = self.tabs.iter().find(|tab| tab.name == name) {
+                        self.selected = Some(tab.position);
+                    }
+                }
+
+                self.tabs_received = true;
+                should_render = true;
+
+                let now = chrono::Utc::now().timestamp();
+                if let Some(name) = self.active_tab_name().map(String::from) {
+                    self.note_focus(&name, now);
+                }
+                let mut new_tabs = false;
+                for tab in &self.tabs {
+                    new_tabs |= self.created.insert_if_absent(&tab.name, now);
+                }
+                if new_tabs {
+                    self.created.save();
+                }
+
+                // the directories are looked up once, and again when tabs come or go, they also
+                // tell tabs sharing a name apart
+                if (self.config.show_git_branch
+                    || self.config.serve_queries
+                    || self.detailed
+                    || self.config.boost_cwd
+                    || self
+                        .tabs
+                        .iter()
+                        .any(|tab| self.duplicate_number(tab).is_some()))
+                    && self.tab_cwds.len() != self.tabs.len()
+                {
+                    host::dump_session_layout();
+                }
+                if self.config.show_git_branch {
+                    self.fetch_branches(false);
+                }
+            }
+            // a branch may have been checked out while room was hidden
+            Event::Visible(true) if self.config.show_git_branch => self.fetch_branches(true),
+            Event::CustomMessage(name, kdl) if name == layout::SESSION_LAYOUT => {
+                self.tab_cwds = layout::tab_cwds(&kdl);
+                if self.config.show_git_branch {
+                    self.fetch_branches(false);
+                }
+                // a `d:` filter may have been waiting for the directories
+                should_render = true;
+            }
+            Event::RunCommandResult(exit_code, stdout, stderr, context) => {
+                if let Some(name) = context.get(cli::TEAR_OFF_CONTEXT) {
+                    let path = String::from_utf8_lossy(&stdout).trim().to_string();
+                    // without the file the session still opens, with a shell
+                    let layout = match exit_code == Some(0) && !path.is_empty() {
+                        true => LayoutInfo::File(path),
+                        false => LayoutInfo::BuiltIn("default".to_string()),
+                    };
+                    close();
+                    host::switch_session_with_layout(Some(name), layout, self.tear_off_cwd.take());
+                }
+                if let Some(cwd) = context.get(GIT_BRANCH_CONTEXT) {
+                    let branch = String::from_utf8_lossy(&stdout).trim().to_string();
+                    let branch = (exit_code == Some(0) && !branch.is_empty()).then_some(branch);
+                    let cwd = PathBuf::from(cwd);
+                    let asked_at = self.branches.get(&cwd).map_or(now_secs(), |(_, at)| *at);
+                    self.branches.insert(cwd, (branch, asked_at));
+                    should_render = true;
+                }
+
+                let what = if context.contains_key(cli::CONTEXT) {
+                    Some("moving tabs")
+                } else if context.contains_key(hooks::CONTEXT) {
+                    Some("on_select_command")
+                } else {
+                    None
+                };
+                if let Some(what) = what.filter(|_| exit_code != Some(0)) {
+                    let stderr = String::from_utf8_lossy(&stderr);
+                    let reason = match (stderr.lines().next(), exit_code) {
+                        (Some(line), _) if !line.trim().is_empty() => line.trim().to_string(),
+                        (_, Some(code)) => format!("exited with {}", code),
+                        (_, None) => "killed".to_string(),
+                    };
+                    self.error = Some(format!("{} failed: {}", what, reason));
+                    should_render = true;
+                }
+            }
+            Event::CustomMessage(name, error) if name == worker::WRITE_FAILED => {
+                self.error = Some(error);
+                should_render = true;
+            }
+
+            Event::PaneUpdate(manifest) => {
+                self.panes = manifest.panes;
+
+                // draw the first update right away, and at most one more per refresh period
+                if self.shows_panes() {
+                    if self.pane_timer {
+                        self.panes_dirty = true;
+                    } else {
+                        self.pane_timer = true;
+                        host::set_timeout(PANE_REFRESH_SECS);
+                        should_render = true;
+                    }
+                }
+            }
+            // the pulse's steps are the shortest timer, one that fires for something else counts
+            // as a step and the step's own timer stands in for it when it fires later
+            Event::Timer(_) if self.pulse > 0 => {
+                self.pulse -= 1;
+                if self.pulse > 0 {
+                    host::set_timeout(PULSE_STEP_SECS);
+                }
+                should_render = true;
+            }
+            // timers can't be told apart, but the pane one is the shortest so it's taken to fire
+            // first, if the toast's beats it the toast just ends when the pane one fires
+            Event::Timer(_) if self.pane_timer => {
+                self.pane_timer = false;
+                should_render = std::mem::take(&mut self.panes_dirty);
+            }
+            Event::Timer(_) if now_secs() + TIMER_SLACK_SECS >= self.toast_due => {
+                self.dismiss_toast()
+            }
+            // the switch is already decided, any key just gets it over with
+            Event::Key(_) if self.toast.is_some() => self.dismiss_toast(),
+            Event::Mouse(mouse) if matches!(self.submode, Submode::List) => match mouse {
+                Mouse::LeftClick(line, _) => {
+                    // a click picks the row, a second click on it switches like Enter does
+                    match self.tab_at_line(line) {
+                        Some(position) if self.selected == Some(position) => {
+                            self.switch_to(position, false)
+                        }
+                        Some(position) => self.selected = Some(position),
+                        None => (),
+                    }
+                    // holding the button on from here drags the tab
+                    self.dragged = self.tab_at_line(line);
+
+                    should_render = true;
+                }
+                Mouse::Hold(line, _) => {
+                    let hovered = self.tab_at_line(line);
+
+                    should_render = hovered != self.hovered;
+                    self.hovered = hovered;
+
+                    // the tab moves to every row it's held over, one move per row crossed
+                    if let (Some(from), Some(to)) = (self.dragged, hovered) {
+                        if from != to {
+                            match self.move_tab(from, to) {
+                                Ok(()) => {
+                                    self.dragged = Some(to);
+                                    self.selected = self
+                                        .selected
+                                        .map(|selected| cli::moved_position(selected, from, to));
+                                }
+                                Err(error) => {
+                                    self.error = Some(error);
+                                    self.dragged = None;
+                                }
+                            }
+                            should_render = true;
+                        }
+                    }
+                }
+                Mouse::Release(_, _) => {
+                    self.dragged = None;
+                    should_render = self.hovered.take().is_some();
+                }
+                Mouse::ScrollUp(_) => {
+                    let selected = self.selected;
+                    self.select_up();
+
+                    should_render = self.selected != selected;
+                }
+                Mouse::ScrollDown(_) => {
+                    let selected = self.selected;
+                    self.select_down();
+
+                    should_render = self.selected != selected;
+                }
+                _ => (),
+            },
+            Event::SessionUpdate(sessions, _) => {
+                if let Some(current) = sessions.iter().find(|session| session.is_current_session) {
+                    should_render = self.config.show_session_name
+                        && self.session_name.as_deref() != Some(current.name.as_str());
+                    self.session_name = Some(current.name.clone());
+                }
+                self.sessions = sessions
+                    .into_iter()
+                    .filter(|session| !session.is_current_session)
+                    .collect();
+                self.sessions_received = true;
+                should_render |= matches!(self.submode, Submode::Global { .. });
+            }
+            Event::Key(key)
+                if keymap::matches(self.config.keymap.cycle_mode, key)
+                    && matches!(
+                        self.submode,
+                        Submode::List
+                            | Submode::Global { .. }
+                            | Submode::Palette { .. }
+                            | Submode::Cheatsheet
+                            | Submode::Panes { .. }
+                            | Submode::Tree { .. }
+                            | Submode::Projects { .. }
+                    ) =>
+            {
+                self.cycle_mode();
+
+                should_render = true;
+            }
+            Event::Key(key) if !matches!(self.submode, Submode::List) => {
+                self.update_submode(key);
+
+                // moving past either end wraps around
+                let count = match self.submode {
+                    Submode::Global { .. } => self.global_lines().len(),
+                    Submode::Palette { .. } => self.palette_actions().len(),
+                    Submode::Panes { .. } => self.pane_entries().len(),
+                    Submode::Tree { .. } => self.tree_nodes().len(),
+                    Submode::Projects { .. } => self.projects().len(),
+                    _ => 0,
+                };
+                if let Submode::Global { selected }
+                | Submode::Palette { selected }
+                | Submode::Panes { selected }
+                | Submode::Tree { selected, .. }
+                | Submode::Projects { selected } = &mut self.submode
+                {
+                    *selected = match count {
+                        0 => 0,
+                        _ if *selected == usize::MAX => count - 1,
+                        _ => *selected % count,
+                    };
+                }
+
+                should_render = true;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.cycle_sort, key) => {
+                self.sort = self.sort.next();
+                self.reset_selection();
+
+                should_render = true;
+            }
+            // without a filter (or with a command) every tab matches, that's not cleanup anymore
+            Event::Key(key)
+                if keymap::matches(self.config.keymap.close_matched, key)
+                    && !self.filter.is_empty()
+                    && !self.typing_command() =>
+            {
+                let matched: Vec<usize> =
+                    self.viewable_tabs_iter().map(|tab| tab.position).collect();
+                if !matched.is_empty() {
+                    let question = match matched.len() {
+                        1 => "close the 1 matching tab?".to_string(),
+                        count => format!("close all {} matching tabs?", count),
+                    };
+                    self.request_close(matched, question, true);
+                }
+
+                should_render = true;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.close, key) => {
+                if let Some(position) = self.selected {
+                    self.run_action(TabAction::Close, position);
+
+                    should_render = true;
+                }
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.next_duplicate, key) => {
+                should_render = self.select_duplicate();
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.repeat, key) => {
+                if let (Some(action), Some(position)) = (self.last_action, self.selected) {
+                    self.run_action(action, position);
+
+                    should_render = true;
+                }
+            }
+            Event::ModeUpdate(mode_info) => {
+                self.bindings = cheatsheet::bindings(&mode_info.keybinds);
+                let changed =
+                    mode_info.session_name.is_some() && mode_info.session_name != self.session_name;
+                if changed {
+                    self.session_name = mode_info.session_name;
+                }
+                should_render = matches!(self.submode, Submode::Cheatsheet)
+                    || (changed && self.config.show_session_name);
+                let palette = mode_info.style.colors;
+                if self.palette.replace(palette) != Some(palette) {
+                    self.theme = Theme::new(self.config.theme, &self.config.colors, Some(&palette));
+                    should_render = true;
+                }
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.permissions, key) => {
+                self.submode = Submode::Permissions;
+
+                should_render = true;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.stats, key) => {
+                self.submode = Submode::Stats;
+
+                should_render = true;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.cheatsheet, key) => {
+                self.submode = Submode::Cheatsheet;
+
+                should_render = true;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.panes, key) => {
+                self.submode = Submode::Panes { selected: 0 };
+
+                should_render = true;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.tree, key) => {
+                self.submode = Submode::Tree {
+                    selected: 0,
+                    expanded: BTreeMap::new(),
+                };
+
+                should_render = true;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.projects, key) => {
+                self.submode = Submode::Projects { selected: 0 };
+
+                should_render = true;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.palette, key) => {
+                self.submode = Submode::Palette { selected: 0 };
+
+                should_render = true;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.global, key) => {
+                self.submode = Submode::Global { selected: 0 };
+
+                should_render = true;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.actions, key) => {
+                if let Some(position) = self.selected {
+                    self.submode = Submode::Actions(Menu::new(position));
+
+                    should_render = true;
+                }
+            }
+            // the second Esc closes
+            Event::Key(Key::Esc) if self.config.esc_clears && !self.filter.is_empty() => {
+                self.clear_filter();
+
+                should_render = true;
+            }
+            Event::Key(Key::Esc | Key::Ctrl('c')) => {
+                close();
+            }
+
+            // Tab is Ctrl+i to the terminal, cycling with it takes over jump_forward's default
+            Event::Key(key)
+                if self.config.keymap.down(key)
+                    || (self.config.tab_cycles && key == Key::Char('\t')) =>
+            {
+                let selected = self.selected;
+                self.select_down();
+
+                should_render = self.selected != selected;
+            }
+            Event::Key(key)
+                if self.config.keymap.up(key)
+                    || (self.config.tab_cycles && key == Key::BackTab) =>
+            {
+                let selected = self.selected;
+                self.select_up();
+
+                should_render = self.selected != selected;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.page_down, key) => {
+                let selected = self.selected;
+                self.select_by(self.page());
+                self.pulse_from(selected);
+
+                should_render = self.selected != selected;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.page_up, key) => {
+                let selected = self.selected;
+                self.select_by(-self.page());
+                self.pulse_from(selected);
+
+                should_render = self.selected != selected;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.select_first, key) => {
+                let selected = self.selected;
+                self.select_by(isize::MIN);
+                self.pulse_from(selected);
+
+                should_render = self.selected != selected;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.select_last, key) => {
+                let selected = self.selected;
+                self.select_by(isize::MAX);
+                self.pulse_from(selected);
+
+                should_render = self.selected != selected;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.jump_back, key) => {
+                self.jump(true);
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.jump_forward, key) => {
+                self.jump(false);
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.density, key) => {
+                self.detailed = !self.detailed;
+                if self.detailed && self.tab_cwds.len() != self.tabs.len() {
+                    host::dump_session_layout();
+                }
+
+                should_render = true;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.reveal_ignored, key) => {
+                self.reveal_ignored = !self.reveal_ignored;
+                self.reset_selection();
+
+                should_render = !self.config.ignore_patterns.is_empty();
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.mark, key) => {
+                let tab = self
+                    .tabs
+                    .iter()
+                    .find(|tab| Some(tab.position) == self.selected);
+                if let Some(name) = tab.map(|tab| tab.name.clone()) {
+                    if !self.marked.remove(&name) {
+                        self.marked.insert(name);
+                    }
+
+                    should_render = true;
+                }
+            }
+            Event::Key(key)
+                if keymap::matches(self.config.keymap.move_up, key)
+                    || keymap::matches(self.config.keymap.move_down, key) =>
+            {
+                let right = keymap::matches(self.config.keymap.move_down, key);
+                if let Err(error) = self.shift_marked(right) {
+                    self.error = Some(error);
+                }
+
+                should_render = true;
+            }
+            Event::Key(key)
+                if keymap::matches(self.config.keymap.copy_name, key)
+                    || keymap::matches(self.config.keymap.copy_index, key) =>
+            {
+                let tab = self
+                    .tabs
+                    .iter()
+                    .find(|tab| Some(tab.position) == self.selected);
+                if let Some(tab) = tab {
+                    self.clipboard = Some(if keymap::matches(self.config.keymap.copy_index, key) {
+                        format!("{}:{}", tab.position + 1, tab.name)
+                    } else {
+                        tab.name.clone()
+                    });
+
+                    should_render = true;
+                }
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.most_used, key) => {
+                if let Some(position) = self.most_used_tab() {
+                    self.switch_to(position, false);
+                }
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.walk_next, key) => {
+                self.walk(false);
+
+                should_render = true;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.walk_previous, key) => {
+                self.walk(true);
+
+                should_render = true;
+            }
+            Event::Key(key) if keymap::matches(self.config.keymap.switch_keep_open, key) => {
+                if let Some(position) = self.selected {
+                    self.switch_to(position, true);
+                    // line up the next stop of the tour
+                    self.select_down();
+                }
+
+                should_render = true;
+            }
+            Event::Key(key) if keymap::is_enter(key) && self.typing_command() => {
+                self.run_command_line();
+
+                should_render = true;
+            }
+            // Y is just a letter of the command's arguments
+            Event::Key(key) if self.config.keymap.confirms(key) && !self.typing_command() => {
+                let tab = self
+                    .tabs
+                    .iter()
+                    .find(|tab| Some(tab.position) == self.selected);
+
+                if let Some(position) = tab.map(|tab| tab.position) {
+                    self.switch_to(position, false);
+                } else if let Some(error) = self.missing_index_error() {
+                    self.error = Some(error);
+
+                    should_render = true;
+                } else if !self.filter.is_empty() {
+                    // nothing matched, so treat the filter as the name of a new tab
+                    close();
+                    log::action(&format!("create tab name={:?}", self.filter));
+                    host::focus_or_create_tab(&self.filter);
+                }
+            }
+            Event::Key(key) if self.config.keymap.search(key).is_some() => {
+                self.filter = self
+                    .config
+                    .keymap
+                    .search(key)
+                    .unwrap_or_default()
+                    .to_string();
+                self.save_filter();
+
+                self.reset_selection();
+
+                should_render = true;
+            }
+            Event::Key(key) if self.config.keymap.clears(key) && !self.filter.is_empty() => {
+                self.clear_filter();
+
+                should_render = true;
+            }
+            Event::Key(Key::Backspace) if !self.filter.is_empty() => {
+                self.filter.pop();
+                self.save_filter();
+
+                // a command acts on the tab selected before typing it
+                if !self.typing_command() {
+                    self.reset_selection();
+                }
+
+                should_render = true;
+            }
+            Event::Key(Key::Char(c)) if c.is_ascii() => {
+                self.filter.push(c);
+                self.save_filter();
+
+                // the directories only come with a dump of the session's layout
+                let (attributes, _) = query::split_attributes(&self.filter);
+                if !attributes.dirs.is_empty() && self.tab_cwds.len() != self.tabs.len() {
+                    host::dump_session_layout();
+                }
+
+                if self.typing_command() {
+                    return true;
+                }
+
+                self.reset_selection();
+
+                if self.config.auto_select_single {
+                    let tabs = self.first_listed(2);
+                    if let [tab] = tabs.as_slice() {
+                        let position = tab.position;
+                        self.switch_to(position, false);
+                    }
+                }
+
+                should_render = true;
+            }
+            _ => (),
+        };
+
+        self.follow_sessions();
+
+        // whatever changed what's on screen may be worth restoring
+        if should_render && self.config.restore_state {
+            self.save_snapshot();
+        }
+        // a hidden room may not see another event for a long while
+        self.flush_writes(hidden);
+
+        should_render
+    }
+
+    fn pipe(&mut self, message: PipeMessage) -> bool {
+        let _timing = log::span(&format!("pipe name={}", message.name));
+        if self.config.serve_queries && message.name == pipes::QUERY {
+            let answer = self.answer_query(message.payload.as_deref());
+            pipes::reply(&message.source, answer.to_string());
+        }
+
+        if message.name == pipes::RESET_STATS {
+            self.stats.reset();
+            self.flush_writes(true);
+            if let PipeSource::Cli(pipe_id) = &message.source {
+                host::unblock_cli_pipe_input(pipe_id);
+            }
+            return matches!(self.submode, Submode::Stats);
+        }
+
+        false
+    }
+
+    fn render(&mut self, rows: usize, cols: usize) -> String {
+        let _timing = log::span(&format!("render rows={} cols={}", rows, cols));
+        let copied = self.clipboard.take().map(|text| clipboard::osc52(&text));
+
+        let mut frame = Frame::new(rows, cols);
+        let mut width = if self.config.centered {
+            cols.min(CENTERED_MAX_COLS)
+        } else {
+            cols
+        };
+        // screen readers would read the box out too
+        let border = self.config.border && !self.config.a11y;
+
+        if border {
+            width = width.saturating_sub(2);
+            let height = rows.saturating_sub(2);
+            self.draw(&mut frame, height, width);
+            // a centered box hugs what's in it, otherwise it frames the whole pane
+            let min_lines = if self.config.centered { 0 } else { height };
+            frame.border(&format!("room — {}", self.mode_name()), width, min_lines);
+            width += 2;
+        } else {
+            self.draw(&mut frame, rows, width);
+        }
+
+        self.tab_lines = frame.tab_lines();
+        self.frame_top = usize::from(border);
+        if self.config.centered {
+            self.frame_top += frame.center(rows, cols, width);
+        }
+        copied.unwrap_or_default() + &frame.into_text()
+    }
+}
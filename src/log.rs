@@ -0,0 +1,128 @@
+use crate::host;
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::time::Instant;
+use zellij_tile::prelude::*;
+
+const PATH: &str = "/data/room.log";
+/// Where the log goes once it's too big, replacing the one before.
+const ROTATED_PATH: &str = "/data/room.log.1";
+const MAX_BYTES: u64 = 1024 * 1024;
+/// What the plugin calls the worker, `register_worker!` names it with a `_worker` suffix.
+const NAME: &str = "logger";
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    /// The lines logged while handling the current event.
+    static LINES: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Appends the lines it's sent to `/data/room.log`, on a thread of its own like the
+/// [`crate::worker::Writer`]. The log starts over in a new file when it grows past a megabyte.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Logger;
+
+impl ZellijWorker<'_> for Logger {
+    /// `payload` holds the lines to add, `message` is the same for all of them.
+    fn on_message(&mut self, _: String, lines: String) {
+        if host::size(PATH) + lines.len() as u64 > MAX_BYTES {
+            let _ = host::rename(PATH, ROTATED_PATH);
+        }
+        // reporting a broken log would need a log of its own
+        let _ = host::append(PATH, &lines);
+    }
+}
+
+/// Starts logging, for `log = true`.
+pub fn enable() {
+    ENABLED.with(|enabled| enabled.set(true));
+}
+
+fn enabled() -> bool {
+    ENABLED.with(Cell::get)
+}
+
+/// Logs something room asked zellij to do, `switch to=3`.
+pub fn action(text: &str) {
+    line("action", text);
+}
+
+/// Logs `event` coming in. The returned timing logs how long handling it took once dropped,
+/// along with everything logged meanwhile.
+pub fn event(event: &Event) -> Timing {
+    if !enabled() {
+        return Timing(None);
+    }
+    let name = describe(event);
+    line("event", &name);
+    Timing(Some((name, Instant::now())))
+}
+
+/// Times something other than an event, `render`.
+pub fn span(name: &str) -> Timing {
+    Timing(enabled().then(|| (name.to_string(), Instant::now())))
+}
+
+/// See [`event`].
+pub struct Timing(Option<(String, Instant)>);
+
+impl Drop for Timing {
+    fn drop(&mut self) {
+        if let Some((name, started)) = &self.0 {
+            let took = started.elapsed().as_secs_f64() * 1000.0;
+            line("timing", &format!("{} ms={:.2}", name, took));
+            flush();
+        }
+    }
+}
+
+/// Hands what was logged so far to the worker.
+pub fn flush() {
+    let lines = LINES.with(|lines| std::mem::take(&mut *lines.borrow_mut()));
+    if !lines.is_empty() {
+        host::post_message_to(PluginMessage::new_to_worker(NAME, "append", &lines));
+    }
+}
+
+/// `2026-10-14T09:41:07.312 event TabUpdate tabs=4`, one line per entry.
+fn line(kind: &str, text: &str) {
+    if !enabled() {
+        return;
+    }
+    let time = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f");
+    LINES.with(|lines| {
+        lines
+            .borrow_mut()
+            .push_str(&format!("{} {} {}\n", time, kind, text))
+    });
+}
+
+/// The kind of `event` along with the little of it worth logging.
+fn describe(event: &Event) -> String {
+    match event {
+        Event::TabUpdate(tabs) => format!("TabUpdate tabs={}", tabs.len()),
+        Event::PaneUpdate(manifest) => format!("PaneUpdate tabs={}", manifest.panes.len()),
+        Event::SessionUpdate(sessions, _) => format!("SessionUpdate sessions={}", sessions.len()),
+        Event::ModeUpdate(mode_info) => format!("ModeUpdate mode={:?}", mode_info.mode),
+        Event::Key(key) => format!("Key key=\"{}\"", key),
+        Event::Mouse(mouse) => format!("Mouse {:?}", mouse),
+        Event::Timer(secs) => format!("Timer secs={}", secs),
+        Event::PermissionRequestResult(status) => {
+            format!("PermissionRequestResult status={:?}", status)
+        }
+        Event::RunCommandResult(exit_code, _, _, context) => format!(
+            "RunCommandResult exit_code={:?} context={:?}",
+            exit_code,
+            context.keys().collect::<Vec<_>>()
+        ),
+        Event::CustomMessage(name, payload) => {
+            format!("CustomMessage name={} bytes={}", name, payload.len())
+        }
+        Event::Visible(visible) => format!("Visible visible={}", visible),
+        other => format!("{:?}", other)
+            .split(['(', ' ', '{'])
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+    }
+}
@@ -0,0 +1,72 @@
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+
+/// A strategy for deciding whether, and how well, a tab matches the filter.
+pub trait Matcher {
+    /// Returns `None` when `haystack` doesn't match `needle`, otherwise a non-negative score
+    /// where higher means a better match.
+    fn score(&self, haystack: &str, needle: &str) -> Option<i64>;
+}
+
+impl Default for Box<dyn Matcher> {
+    fn default() -> Self {
+        Box::<Skim>::default()
+    }
+}
+
+/// Builds the matcher configured with `matcher = "..."`.
+pub fn from_name(name: &str) -> Option<Box<dyn Matcher>> {
+    match name {
+        "skim" => Some(Box::<Skim>::default()),
+        "substring" => Some(Box::new(Substring)),
+        "prefix" => Some(Box::new(Prefix)),
+        _ => None,
+    }
+}
+
+/// Fuzzy matching, the needle's characters only have to appear in order.
+#[derive(Default)]
+pub struct Skim(SkimMatcherV2);
+
+impl Matcher for Skim {
+    fn score(&self, haystack: &str, needle: &str) -> Option<i64> {
+        self.0.fuzzy_match(haystack, needle)
+    }
+}
+
+/// The needle has to appear as is, earlier occurrences score higher.
+pub struct Substring;
+
+impl Matcher for Substring {
+    fn score(&self, haystack: &str, needle: &str) -> Option<i64> {
+        haystack
+            .find(needle)
+            .map(|start| (haystack.len() - start) as i64)
+    }
+}
+
+/// The needle has to appear at the start of a word, earlier words score higher.
+pub struct Prefix;
+
+impl Matcher for Prefix {
+    fn score(&self, haystack: &str, needle: &str) -> Option<i64> {
+        if needle.is_empty() {
+            return Some(0);
+        }
+
+        word_starts(haystack)
+            .find(|start| haystack[*start..].starts_with(needle))
+            .map(|start| (haystack.len() - start) as i64)
+    }
+}
+
+/// Byte offsets of every word in `haystack`, words being runs of alphanumeric characters.
+pub fn word_starts(haystack: &str) -> impl Iterator<Item = usize> + '_ {
+    let mut previous = None;
+    haystack.char_indices().filter_map(move |(start, c)| {
+        let at_boundary =
+            c.is_alphanumeric() && previous.is_none_or(|p: char| !p.is_alphanumeric());
+        previous = Some(c);
+        at_boundary.then_some(start)
+    })
+}
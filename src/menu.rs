@@ -0,0 +1,108 @@
+/// Something that can be done to the selected tab from the quick-actions menu.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TabAction {
+    Switch,
+    Close,
+    CloseOthers,
+    Rename,
+    Pin,
+    Protect,
+    /// Gives the tab the next color, or none after the last one.
+    Color,
+    /// Opens the icon picker.
+    Icon,
+    TearOff,
+    /// Moves the tab one step to the left, with `enable_move`.
+    MoveLeft,
+    /// Moves the tab one step to the right, with `enable_move`.
+    MoveRight,
+    /// Trades places with the active tab, with `enable_move`.
+    Swap,
+    /// Opens the tab's focused pane again, floating over the current tab.
+    Borrow,
+    /// Opens every pane of the tab again in the current tab, then closes the tab.
+    Merge,
+}
+
+impl TabAction {
+    /// Every action, in the order the menu lists them.
+    pub const ALL: [TabAction; 14] = [
+        TabAction::Switch,
+        TabAction::Close,
+        TabAction::CloseOthers,
+        TabAction::Rename,
+        TabAction::Pin,
+        TabAction::Protect,
+        TabAction::Color,
+        TabAction::Icon,
+        TabAction::TearOff,
+        TabAction::MoveLeft,
+        TabAction::MoveRight,
+        TabAction::Swap,
+        TabAction::Borrow,
+        TabAction::Merge,
+    ];
+
+    /// Whether the repeat key can run the action again on another tab. Everything can, moving a
+    /// tab a step included, but switching and tearing a tab off (both leave room), renaming and
+    /// picking an icon (both meant for one tab) and swapping with the current tab.
+    pub fn repeatable(self) -> bool {
+        !matches!(
+            self,
+            TabAction::Switch
+                | TabAction::Rename
+                | TabAction::Icon
+                | TabAction::TearOff
+                | TabAction::Swap
+        )
+    }
+
+    /// `on` tells whether a toggle (pin, protect) is currently set for the tab.
+    pub fn label(self, on: bool) -> &'static str {
+        match self {
+            TabAction::Switch => "switch",
+            TabAction::Close => "close",
+            TabAction::CloseOthers => "close others",
+            TabAction::Rename => "rename",
+            TabAction::Pin if on => "unpin",
+            TabAction::Pin => "pin",
+            TabAction::Protect if on => "unprotect",
+            TabAction::Protect => "protect",
+            TabAction::Color => "next color",
+            TabAction::Icon => "icon",
+            TabAction::TearOff => "move to a new session",
+            TabAction::MoveLeft => "move left",
+            TabAction::MoveRight => "move right",
+            TabAction::Swap => "swap with the current tab",
+            TabAction::Borrow => "open its pane floating here",
+            TabAction::Merge => "merge into the current tab",
+        }
+    }
+}
+
+/// The quick-actions menu opened on a tab, `selected` indexes [`TabAction::ALL`].
+pub struct Menu {
+    pub position: usize,
+    pub selected: usize,
+}
+
+impl Menu {
+    pub fn new(position: usize) -> Self {
+        Menu {
+            position,
+            selected: 0,
+        }
+    }
+
+    pub fn action(&self) -> TabAction {
+        TabAction::ALL[self.selected]
+    }
+
+    pub fn select_down(&mut self) {
+        self.selected = (self.selected + 1) % TabAction::ALL.len();
+    }
+
+    pub fn select_up(&mut self) {
+        self.selected = (self.selected + TabAction::ALL.len() - 1) % TabAction::ALL.len();
+    }
+}
@@ -0,0 +1,43 @@
+use crate::host;
+use crate::keymap::Keymap;
+use crate::snippet;
+use crate::worker;
+use std::collections::BTreeMap;
+
+/// Exists once the onboarding page has been dismissed.
+const PATH: &str = "/data/onboarded";
+
+/// Whether the onboarding page was dismissed before, by this or an earlier instance.
+pub fn seen() -> bool {
+    host::exists(PATH)
+}
+
+pub fn mark_seen() {
+    worker::write(PATH, "");
+}
+
+/// The page shown the first time room loads.
+pub fn lines(keymap: &Keymap) -> Vec<String> {
+    let mut lines = vec![
+        "welcome to room, a switcher for the tabs of your session".to_string(),
+        String::new(),
+        "zellij asks whether room may read and change its state, room can't list or switch tabs"
+            .to_string(),
+        "without that, some options ask for more (like running git for branches)".to_string(),
+        String::new(),
+        "type to filter the tabs, Enter to switch to the selected one, Esc to close".to_string(),
+        format!(
+            "Up/Down or {}/{} to move, {} for a tab's actions, {} to cycle the sort",
+            keymap.select_up, keymap.select_down, keymap.actions, keymap.cycle_sort
+        ),
+        format!(
+            "{} for other sessions, {} for zellij actions, {} for the keybindings",
+            keymap.global, keymap.palette, keymap.cheatsheet
+        ),
+        String::new(),
+        "to open room with a key, bind it in zellij's config:".to_string(),
+    ];
+    let binding = snippet::lines(&BTreeMap::new());
+    lines.extend(binding.into_iter().map(|line| format!("  {}", line)));
+    lines
+}
@@ -0,0 +1,81 @@
+use crate::host;
+use crate::log;
+use std::path::Path;
+
+/// A zellij action offered by the command palette. They all act on what was focused before
+/// room opened, so room gets out of the way first.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PaletteAction {
+    NewPane,
+    NewFloatingPane,
+    NewTab,
+    ClosePane,
+    ToggleFullscreen,
+    ToggleFloating,
+    TogglePaneFrames,
+    ToggleSync,
+    NextSwapLayout,
+    PreviousSwapLayout,
+    EditScrollback,
+    ClearScreen,
+    Detach,
+}
+
+impl PaletteAction {
+    /// Every action, in the order the palette lists them without a filter.
+    pub const ALL: [PaletteAction; 13] = [
+        PaletteAction::NewPane,
+        PaletteAction::NewFloatingPane,
+        PaletteAction::NewTab,
+        PaletteAction::ClosePane,
+        PaletteAction::ToggleFullscreen,
+        PaletteAction::ToggleFloating,
+        PaletteAction::TogglePaneFrames,
+        PaletteAction::ToggleSync,
+        PaletteAction::NextSwapLayout,
+        PaletteAction::PreviousSwapLayout,
+        PaletteAction::EditScrollback,
+        PaletteAction::ClearScreen,
+        PaletteAction::Detach,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PaletteAction::NewPane => "new pane",
+            PaletteAction::NewFloatingPane => "new floating pane",
+            PaletteAction::NewTab => "new tab",
+            PaletteAction::ClosePane => "close pane",
+            PaletteAction::ToggleFullscreen => "toggle fullscreen",
+            PaletteAction::ToggleFloating => "toggle between floating and embedded",
+            PaletteAction::TogglePaneFrames => "toggle pane frames",
+            PaletteAction::ToggleSync => "toggle sync input of the tab",
+            PaletteAction::NextSwapLayout => "next swap layout",
+            PaletteAction::PreviousSwapLayout => "previous swap layout",
+            PaletteAction::EditScrollback => "edit scrollback",
+            PaletteAction::ClearScreen => "clear screen",
+            PaletteAction::Detach => "detach",
+        }
+    }
+
+    /// Closes room then runs the action, new panes open in `cwd`.
+    pub fn run(self, cwd: &Path) {
+        crate::close();
+        log::action(&format!("palette action={:?}", self.label()));
+
+        match self {
+            PaletteAction::NewPane => host::open_terminal(cwd),
+            PaletteAction::NewFloatingPane => host::open_terminal_floating(cwd, None),
+            PaletteAction::NewTab => host::new_tab(),
+            PaletteAction::ClosePane => host::close_focus(),
+            PaletteAction::ToggleFullscreen => host::toggle_focus_fullscreen(),
+            PaletteAction::ToggleFloating => host::toggle_pane_embed_or_eject(),
+            PaletteAction::TogglePaneFrames => host::toggle_pane_frames(),
+            PaletteAction::ToggleSync => host::toggle_active_tab_sync(),
+            PaletteAction::NextSwapLayout => host::next_swap_layout(),
+            PaletteAction::PreviousSwapLayout => host::previous_swap_layout(),
+            PaletteAction::EditScrollback => host::edit_scrollback(),
+            PaletteAction::ClearScreen => host::clear_screen(),
+            PaletteAction::Detach => host::detach(),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use zellij_tile::prelude::*;
+
+/// What room does with `permission`, to tell which features a denial takes away.
+pub fn purpose(permission: PermissionType) -> &'static str {
+    match permission {
+        PermissionType::ReadApplicationState => "listing tabs, panes and sessions",
+        PermissionType::ChangeApplicationState => "switching, renaming and closing tabs",
+        PermissionType::OpenTerminalsOrPlugins => "opening panes from the palette",
+        PermissionType::MessageAndLaunchOtherPlugins => "publishing switches and answering queries",
+        PermissionType::ReadCliPipes => "answering queries from the command line",
+        PermissionType::RunCommands => "git branches, the on_select hook and moving tabs",
+        _ => "nothing",
+    }
+}
@@ -0,0 +1,59 @@
+use crate::host;
+use std::collections::BTreeMap;
+use zellij_tile::prelude::*;
+
+/// Sent right before room switches tabs.
+pub const PRE_SWITCH: &str = "room:pre_switch";
+/// Sent right after room switched tabs.
+pub const POST_SWITCH: &str = "room:post_switch";
+
+/// Tells other plugins (e.g. a status bar) about a switch to `tab`. Every message carries the
+/// tab's `name` and 0 indexed `position` as arguments, `from` naming the tab we left when we know
+/// it, and the tab's name as the payload. `destination` is a plugin url, or `broadcast` to reach
+/// every running plugin.
+pub fn publish(destination: &str, name: &str, tab: &TabInfo, from: Option<&str>) {
+    let mut args = BTreeMap::from([
+        ("name".to_string(), tab.name.clone()),
+        ("position".to_string(), tab.position.to_string()),
+    ]);
+    if let Some(from) = from {
+        args.insert("from".to_string(), from.to_string());
+    }
+
+    let mut message = MessageToPlugin::new(name)
+        .with_payload(tab.name.clone())
+        .with_args(args);
+    if destination != "broadcast" {
+        message = message.with_plugin_url(destination);
+    }
+
+    host::pipe_message_to_plugin(message);
+}
+
+/// Clears the usage stats, `zellij pipe room:reset-stats` (which takes `serve_queries` for room
+/// to hear it).
+pub const RESET_STATS: &str = "room:reset-stats";
+
+/// Asks room for the tabs matching a filter, the payload being JSON like
+/// `{"kind": "tabs", "filter": "api"}`.
+pub const QUERY: &str = "room:query";
+/// The answer to a [`QUERY`] sent from another plugin, the payload holding the JSON.
+pub const QUERY_RESULT: &str = "room:query_result";
+
+/// Answers whoever sent a [`QUERY`], printing to the terminal of a `zellij pipe` or messaging the
+/// plugin back.
+pub fn reply(source: &PipeSource, payload: String) {
+    match source {
+        PipeSource::Cli(pipe_id) => {
+            host::cli_pipe_output(pipe_id, &payload);
+            host::unblock_cli_pipe_input(pipe_id);
+        }
+        PipeSource::Plugin(plugin_id) => host::pipe_message_to_plugin(
+            MessageToPlugin::new(QUERY_RESULT)
+                .with_destination_plugin_id(*plugin_id)
+                .with_payload(payload),
+        ),
+        // a keybinding has nobody to read the answer
+        PipeSource::Keybind => (),
+    }
+}
@@ -0,0 +1,108 @@
+use crate::host;
+use crate::log;
+use std::path::PathBuf;
+use zellij_tile::prelude::*;
+
+/// A project configured with `project_<name> = "cwd=~/code/api;layout=dev;command=nvim ."`,
+/// opened from the projects mode. Every field is optional.
+pub struct Project {
+    pub name: String,
+    pub cwd: Option<PathBuf>,
+    /// A layout file (ending in `.kdl`) or the name of a built-in layout, which can open a
+    /// whole set of tabs.
+    pub layout: Option<String>,
+    pub command: Option<String>,
+}
+
+impl Project {
+    pub fn parse(name: &str, value: &str) -> Result<Self, String> {
+        let mut project = Project {
+            name: name.to_string(),
+            cwd: None,
+            layout: None,
+            command: None,
+        };
+
+        for field in value
+            .split(';')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+        {
+            let Some((key, value)) = field.split_once('=') else {
+                return Err(format!("\"{}\" isn't a key=value field", field));
+            };
+            let value = value.trim().to_string();
+            match key.trim() {
+                "cwd" => project.cwd = Some(PathBuf::from(value)),
+                "layout" => project.layout = Some(value),
+                "command" => project.command = Some(value),
+                key => {
+                    return Err(format!(
+                        "unknown field \"{}\", expected cwd, layout or command",
+                        key
+                    ))
+                }
+            }
+        }
+
+        Ok(project)
+    }
+
+    /// What's listed next to the name.
+    pub fn description(&self) -> String {
+        [
+            self.cwd.as_ref().map(|cwd| cwd.display().to_string()),
+            self.layout.clone(),
+            self.command.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("  ")
+    }
+
+    /// Opens the project's tabs. A layout brings its own tabs (and names), otherwise a tab
+    /// named after the project is made in its directory, running its command.
+    pub fn open(&self) {
+        log::action(&format!("open project={:?}", self.name));
+        match &self.layout {
+            Some(layout) if layout.ends_with(".kdl") => {
+                host::new_tabs_with_layout_info(LayoutInfo::File(layout.clone()))
+            }
+            Some(layout) => host::new_tabs_with_layout_info(LayoutInfo::BuiltIn(layout.clone())),
+            None => host::new_tabs_with_layout(&self.layout_kdl()),
+        }
+    }
+
+    /// A layout opening the project as a single tab, its command running in the one pane.
+    pub fn layout_kdl(&self) -> String {
+        let mut tab = format!("tab name=\"{}\"", escape(&self.name));
+        if let Some(cwd) = &self.cwd {
+            tab.push_str(&format!(" cwd=\"{}\"", escape(&cwd.to_string_lossy())));
+        }
+
+        let pane = match self.command.as_deref().map(str::split_whitespace) {
+            Some(mut words) => {
+                let program = words.next().unwrap_or_default();
+                let args: Vec<String> = words.map(|arg| format!("\"{}\"", escape(arg))).collect();
+                if args.is_empty() {
+                    format!("pane command=\"{}\"", escape(program))
+                } else {
+                    format!(
+                        "pane command=\"{}\" {{ args {}; }}",
+                        escape(program),
+                        args.join(" ")
+                    )
+                }
+            }
+            None => "pane".to_string(),
+        };
+
+        format!("layout {{\n    {} {{\n        {}\n    }}\n}}", tab, pane)
+    }
+}
+
+/// Makes `value` safe inside a KDL string.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,94 @@
+/// The filter line, interpreted.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Query<'a> {
+    /// Matched against the tab's index and name with the configured matcher.
+    Text(&'a str),
+    /// Only tabs whose index starts with these digits.
+    Index(&'a str),
+    /// Only tabs whose index lies within the range, both ends included.
+    Range(usize, usize),
+    /// Only the tab at exactly this index, typed as `:42`.
+    Goto(usize),
+    /// A `:` command, what follows the colon. Every tab stays listed to pick the target from.
+    Command(&'a str),
+}
+
+/// Parses an already normalized filter. Purely numeric queries are only treated as indices
+/// when `strict_index` is set, otherwise they're fuzzy matched like any other text.
+pub fn parse(filter: &str, strict_index: bool) -> Query<'_> {
+    if filter.is_empty() {
+        return Query::Text(filter);
+    }
+
+    if let Some(Ok(index)) = filter.strip_prefix(':').map(str::parse) {
+        return Query::Goto(index);
+    }
+
+    if let Some(line) = filter.strip_prefix(':') {
+        return Query::Command(line);
+    }
+
+    if filter.chars().all(|c| c.is_ascii_digit()) {
+        return if strict_index {
+            Query::Index(filter)
+        } else {
+            Query::Text(filter)
+        };
+    }
+
+    // `2-5`, or `2-` while the end is still being typed
+    if let Some((start, end)) = filter.split_once('-') {
+        let start = start.parse::<usize>();
+        let end = if end.is_empty() {
+            Ok(usize::MAX)
+        } else {
+            end.parse::<usize>()
+        };
+
+        if let (Ok(start), Ok(end)) = (start, end) {
+            return Query::Range(start.min(end), start.max(end));
+        }
+    }
+
+    Query::Text(filter)
+}
+
+/// The scoped `key:value` tokens of a filter, every one of them has to match.
+#[derive(Default)]
+pub struct Attributes<'a> {
+    /// `color:red`, a (partial) color name, `color:` alone asks for any color.
+    pub colors: Vec<&'a str>,
+    /// `c:vim`, part of a command running in one of the tab's panes.
+    pub commands: Vec<&'a str>,
+    /// `d:api`, part of the tab's working directory.
+    pub dirs: Vec<&'a str>,
+    /// `t:3`, the start of the tab's index.
+    pub indices: Vec<&'a str>,
+    /// `s:dev`, part of the session's name, only tabs of other sessions have one to match.
+    pub sessions: Vec<&'a str>,
+}
+
+/// Splits the attribute tokens off a filter, returning them along with what's left to match
+/// the usual way.
+pub fn split_attributes(filter: &str) -> (Attributes<'_>, String) {
+    let mut attributes = Attributes::default();
+    let mut rest = Vec::new();
+    for word in filter.split(' ') {
+        let Some((key, value)) = word.split_once(':') else {
+            rest.push(word);
+            continue;
+        };
+
+        match key {
+            "color" => attributes.colors.push(value),
+            "c" => attributes.commands.push(value),
+            "d" => attributes.dirs.push(value),
+            "t" => attributes.indices.push(value),
+            "s" => attributes.sessions.push(value),
+            // `:3` and `:rename` are for the query itself
+            _ => rest.push(word),
+        }
+    }
+
+    (attributes, rest.join(" "))
+}
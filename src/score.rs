@@ -0,0 +1,65 @@
+use crate::matcher::word_starts;
+use std::path::Path;
+
+/// Typing the exact index should always put that tab first.
+const EXACT_INDEX_BONUS: i64 = 100;
+/// The name starts with the query, e.g. `ser` for "server".
+const NAME_PREFIX_BONUS: i64 = 50;
+/// A later word of the name starts with the query, e.g. `api` for "web-api".
+const WORD_BOUNDARY_BONUS: i64 = 25;
+/// Per directory a tab's working directory shares with where room was opened.
+const SHARED_DIR_BONUS: i64 = 10;
+/// What the most recently visited tab gets for frecency, every older visit one less.
+const RECENT_POINTS: i64 = 10;
+/// Switches count up to this many for frecency, so tabs used for ages don't win every time.
+const MAX_SWITCH_POINTS: i64 = 50;
+/// Tabs up to this many positions away from the active one get proximity points.
+const PROXIMITY_RANGE: i64 = 10;
+
+/// Extra points on top of the matcher's score, computed from the tab's index and name on their
+/// own rather than from the `"index: name"` string the matcher sees. Both the name and the
+/// query are expected to be normalized (e.g. lowercased) by the caller already.
+pub fn bonus(index: &str, name: &str, query: &str) -> i64 {
+    if query.is_empty() {
+        return 0;
+    }
+
+    let mut bonus = 0;
+    if index == query {
+        bonus += EXACT_INDEX_BONUS;
+    }
+
+    match word_starts(name).find(|start| name[*start..].starts_with(query)) {
+        Some(0) => bonus += NAME_PREFIX_BONUS,
+        Some(_) => bonus += WORD_BOUNDARY_BONUS,
+        None => (),
+    }
+
+    bonus
+}
+
+/// Extra points for a tab working near `near`, more the deeper the directories the two paths
+/// share. Sharing only the root doesn't count.
+pub fn cwd_bonus(cwd: &Path, near: &Path) -> i64 {
+    let shared = cwd
+        .components()
+        .zip(near.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    shared.saturating_sub(1) as i64 * SHARED_DIR_BONUS
+}
+
+/// How often and how recently a tab got picked, `switches` being how many times room switched to
+/// it and `recent` how many other tabs were visited through room since (`None` when it never
+/// was).
+pub fn frecency(switches: u64, recent: Option<usize>) -> i64 {
+    let recency = recent.map_or(0, |recent| {
+        RECENT_POINTS.saturating_sub(recent as i64).max(0)
+    });
+    (switches as i64).min(MAX_SWITCH_POINTS) + recency
+}
+
+/// More points the closer `position` is to the active tab's.
+pub fn proximity(position: usize, active: usize) -> i64 {
+    (PROXIMITY_RANGE - position.abs_diff(active) as i64).max(0)
+}
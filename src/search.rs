@@ -0,0 +1,66 @@
+use crate::collate::{self, CollationKey};
+use std::collections::HashMap;
+use zellij_tile::prelude::*;
+
+/// The strings a tab is matched on, lowercased (and with `fold_accents` stripped of their
+/// accents) once when the tab changes rather than on every keystroke.
+pub struct SearchStr {
+    /// What the strings were made from, to tell whether the tab changed since.
+    name: String,
+    layout: Option<String>,
+    /// The 1 indexed position.
+    pub index: String,
+    pub name_lowercase: String,
+    /// `"index: name"`, followed by the swap layout when it's shown.
+    pub haystack: String,
+    pub collation: CollationKey,
+}
+
+impl SearchStr {
+    fn new(tab: &TabInfo, with_layout: bool, fold: bool) -> Self {
+        let normalize = |text: &str| match fold {
+            true => collate::fold(&text.to_lowercase()),
+            false => text.to_lowercase(),
+        };
+        let index = (tab.position + 1).to_string();
+        let name_lowercase = normalize(&tab.name);
+        let mut haystack = format!("{}: {}", index, name_lowercase);
+        if with_layout {
+            if let Some(layout) = &tab.active_swap_layout_name {
+                haystack.push(' ');
+                haystack.push_str(&normalize(layout));
+            }
+        }
+
+        SearchStr {
+            name: tab.name.clone(),
+            layout: tab.active_swap_layout_name.clone(),
+            index,
+            name_lowercase,
+            haystack,
+            collation: collate::key(&tab.name),
+        }
+    }
+
+    fn is_for(&self, tab: &TabInfo) -> bool {
+        self.name == tab.name && self.layout == tab.active_swap_layout_name
+    }
+}
+
+/// Brings `cache` (by tab position) in line with `tabs`, only rebuilding the strings of the tabs
+/// that changed.
+pub fn refresh(
+    cache: &mut HashMap<usize, SearchStr>,
+    tabs: &[TabInfo],
+    with_layout: bool,
+    fold: bool,
+) {
+    let mut previous = std::mem::take(cache);
+    for tab in tabs {
+        let search_str = previous
+            .remove(&tab.position)
+            .filter(|search_str| search_str.is_for(tab))
+            .unwrap_or_else(|| SearchStr::new(tab, with_layout, fold));
+        cache.insert(tab.position, search_str);
+    }
+}
@@ -0,0 +1,101 @@
+use zellij_tile::prelude::*;
+
+/// What a run of listed tabs is, in the order the sections are listed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Recent,
+    Pinned,
+    Tabs,
+}
+
+impl Kind {
+    pub fn title(self) -> &'static str {
+        match self {
+            Kind::Recent => "recent",
+            Kind::Pinned => "pinned",
+            Kind::Tabs => "tabs",
+        }
+    }
+}
+
+pub struct Section<'a> {
+    pub kind: Kind,
+    /// The first tabs of the section, the ones that can make it on screen.
+    pub tabs: Vec<&'a TabInfo>,
+    /// How many tabs the section holds, shown or not.
+    pub total: usize,
+}
+
+/// A line of the list.
+pub enum Line<'a> {
+    Header(&'static str),
+    Tab(&'a TabInfo),
+    /// How many tabs didn't fit.
+    More(usize),
+}
+
+/// Lays `sections` out on `rows` lines, with every tab taking `tab_height` of them. Headers are
+/// only drawn when there's more than one section. When the tabs don't all fit, the rows are
+/// shared out evenly (a small section leaving what it doesn't need to the others) and every
+/// section scrolls on its own, just far enough to show the selected tab when it holds it.
+pub fn layout<'a>(
+    sections: &[Section<'a>],
+    rows: usize,
+    tab_height: usize,
+    selected: Option<usize>,
+) -> Vec<Line<'a>> {
+    let headers = if sections.len() > 1 {
+        sections.len()
+    } else {
+        0
+    };
+    let total: usize = sections.iter().map(|section| section.total).sum();
+
+    let heights: Vec<usize> = if total * tab_height + headers <= rows {
+        sections.iter().map(|section| section.total).collect()
+    } else {
+        // a line is kept for saying how many tabs are left out
+        let mut left = rows.saturating_sub(headers + 1) / tab_height.max(1);
+        let mut heights = vec![0; sections.len()];
+        let mut smallest_first: Vec<usize> = (0..sections.len()).collect();
+        smallest_first.sort_by_key(|index| sections[*index].tabs.len());
+        for (done, index) in smallest_first.into_iter().enumerate() {
+            let share = left / (sections.len() - done);
+            heights[index] = sections[index].tabs.len().min(share);
+            left -= heights[index];
+        }
+        // what the even shares rounded away goes to the first sections that can use it
+        for (height, section) in heights.iter_mut().zip(sections) {
+            let extra = left.min(section.tabs.len() - *height);
+            *height += extra;
+            left -= extra;
+        }
+        heights
+    };
+
+    let mut lines = Vec::with_capacity(rows);
+    for (section, height) in sections.iter().zip(heights) {
+        if headers > 0 {
+            lines.push(Line::Header(section.kind.title()));
+        }
+        let start = section
+            .tabs
+            .iter()
+            .position(|tab| Some(tab.position) == selected)
+            .map_or(0, |index| (index + 1).saturating_sub(height));
+        lines.extend(
+            section.tabs[start..start + height]
+                .iter()
+                .map(|tab| Line::Tab(tab)),
+        );
+    }
+
+    let shown = lines
+        .iter()
+        .filter(|line| matches!(line, Line::Tab(_)))
+        .count();
+    if shown < total {
+        lines.push(Line::More(total - shown));
+    }
+    lines
+}
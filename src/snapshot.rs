@@ -0,0 +1,48 @@
+use crate::host;
+use crate::sort::Sort;
+use crate::worker;
+
+const PATH: &str = "/data/state";
+
+/// What room looked like, saved as it changes so a resurrected session brings it back as it
+/// was. Pins and protected tabs are saved on their own already.
+pub struct Snapshot {
+    pub sort: Sort,
+    /// The mode's name, as in the prompt's badge.
+    pub mode: String,
+    /// The name of the selected tab, positions don't survive a resurrection.
+    pub selected: Option<String>,
+    pub filter: String,
+}
+
+impl Snapshot {
+    pub fn load() -> Option<Self> {
+        let contents = host::read_to_string(PATH).ok()?;
+        let mut lines = contents.lines();
+
+        let sort = Sort::from_name(lines.next()?).unwrap_or_default();
+        let mode = lines.next()?.to_string();
+        let selected = Some(lines.next()?.to_string()).filter(|name| !name.is_empty());
+        // the filter can't hold a newline, it's typed one character at a time
+        let filter = lines.next().unwrap_or_default().to_string();
+
+        Some(Snapshot {
+            sort,
+            mode,
+            selected,
+            filter,
+        })
+    }
+
+    pub fn save(&self) {
+        let contents = format!(
+            "{}\n{}\n{}\n{}",
+            self.sort.name(),
+            self.mode,
+            self.selected.as_deref().unwrap_or_default(),
+            self.filter
+        );
+
+        worker::write(PATH, &contents);
+    }
+}
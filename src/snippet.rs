@@ -0,0 +1,25 @@
+use std::collections::BTreeMap;
+
+/// Where zellij's docs suggest keeping plugins, room can't learn the url it was loaded from.
+const PLUGIN_URL: &str = "file:~/.config/zellij/plugins/room.wasm";
+
+/// A keybinding for zellij's config launching room with `configuration`, the options it was
+/// given this time.
+pub fn lines(configuration: &BTreeMap<String, String>) -> Vec<String> {
+    let mut lines = vec![
+        "bind \"Ctrl y\" {".to_string(),
+        format!("    LaunchOrFocusPlugin \"{}\" {{", PLUGIN_URL),
+        "        floating true".to_string(),
+    ];
+    for (name, value) in configuration {
+        lines.push(format!("        {} \"{}\"", name, escape(value)));
+    }
+    lines.push("    }".to_string());
+    lines.push("}".to_string());
+    lines
+}
+
+/// Makes `value` safe inside a KDL string.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
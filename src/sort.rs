@@ -0,0 +1,50 @@
+/// How matching tabs are ordered, after the pinned ones.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+    /// Best match first.
+    #[default]
+    Score,
+    /// Alphabetical, accented and non-latin names included, see [`crate::collate`].
+    Name,
+    /// Tabs with the most panes first, handy to tell the real work from empty leftovers.
+    Panes,
+    /// Tabs room saw first come first, to find the stale ones.
+    Oldest,
+    /// Tabs room saw last come first.
+    Newest,
+}
+
+impl Sort {
+    /// Every sort, in the order the sort key cycles through them.
+    const ALL: [Sort; 5] = [
+        Sort::Score,
+        Sort::Name,
+        Sort::Panes,
+        Sort::Oldest,
+        Sort::Newest,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Sort::ALL.into_iter().find(|sort| sort.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Sort::Score => "score",
+            Sort::Name => "name",
+            Sort::Panes => "panes",
+            Sort::Oldest => "oldest",
+            Sort::Newest => "newest",
+        }
+    }
+
+    /// Whether rows show how old their tab is.
+    pub fn by_age(self) -> bool {
+        matches!(self, Sort::Oldest | Sort::Newest)
+    }
+
+    pub fn next(self) -> Self {
+        let index = Sort::ALL.iter().position(|sort| *sort == self).unwrap_or(0);
+        Sort::ALL[(index + 1) % Sort::ALL.len()]
+    }
+}
@@ -0,0 +1,132 @@
+use crate::host;
+use crate::worker;
+use std::collections::BTreeMap;
+
+const PATH: &str = "/data/stats";
+
+/// How much a tab got used.
+#[derive(Default, Clone, Copy)]
+pub struct TabStats {
+    pub visits: u64,
+    /// Seconds the tab spent focused.
+    pub focus_secs: i64,
+    /// How many times room switched to the tab.
+    pub switches: u64,
+}
+
+/// Usage of every tab, by name, kept in `/data` and never sent anywhere. Room only sees focus
+/// changes while it runs, so a tab is credited with the time from the moment room saw it focused
+/// to the next focus room sees, which is only an approximation of how long it really stayed
+/// focused.
+#[derive(Default)]
+pub struct Stats {
+    tabs: BTreeMap<String, TabStats>,
+    /// The tab last seen focused, and since when (a unix timestamp).
+    focused: Option<(String, i64)>,
+}
+
+impl Stats {
+    /// Reads the stats saved by a previous instance, or starts empty ones.
+    pub fn load() -> Self {
+        let Ok(contents) = host::read_to_string(PATH) else {
+            return Self::default();
+        };
+
+        let mut lines = contents.lines();
+        let focused = lines.next().and_then(|line| {
+            let (since, name) = line.split_once('\t')?;
+            Some((name.to_string(), since.parse().ok()?))
+        });
+        let tabs = lines
+            .filter_map(|line| {
+                let mut fields = line.splitn(4, '\t');
+                let visits = fields.next()?.parse().ok()?;
+                let focus_secs = fields.next()?.parse().ok()?;
+                let switches = fields.next()?.parse().ok()?;
+                let name = fields.next()?;
+                let stats = TabStats {
+                    visits,
+                    focus_secs,
+                    switches,
+                };
+                Some((name.to_string(), stats))
+            })
+            .collect();
+
+        Stats { tabs, focused }
+    }
+
+    pub fn save(&self) {
+        let mut contents = match &self.focused {
+            Some((name, since)) => format!("{}\t{}", since, name),
+            None => String::new(),
+        };
+        for (name, stats) in &self.tabs {
+            contents.push_str(&format!(
+                "\n{}\t{}\t{}\t{}",
+                stats.visits, stats.focus_secs, stats.switches, name
+            ));
+        }
+
+        worker::write(PATH, &contents);
+    }
+
+    /// Notes that `name` is focused at `now`, crediting the previous tab with the time since it
+    /// got focused. Returns whether anything changed.
+    pub fn focus(&mut self, name: &str, now: i64) -> bool {
+        if let Some((previous, since)) = &self.focused {
+            if previous == name {
+                return false;
+            }
+
+            let previous = self.tabs.entry(previous.clone()).or_default();
+            previous.focus_secs += (now - since).max(0);
+        }
+
+        self.tabs.entry(name.to_string()).or_default().visits += 1;
+        self.focused = Some((name.to_string(), now));
+        true
+    }
+
+    /// Counts a switch to `name` made through room.
+    pub fn switched(&mut self, name: &str) {
+        self.tabs.entry(name.to_string()).or_default().switches += 1;
+    }
+
+    /// Forgets everything, for a fresh start.
+    pub fn reset(&mut self) {
+        *self = Stats::default();
+        self.save();
+    }
+
+    /// Follows a tab to its new name, since stats only know tabs by name.
+    pub fn rename(&mut self, old_name: &str, new_name: &str) {
+        if let Some(stats) = self.tabs.remove(old_name) {
+            self.tabs.insert(new_name.to_string(), stats);
+        }
+        if let Some((name, _)) = &mut self.focused {
+            if name == old_name {
+                *name = new_name.to_string();
+            }
+        }
+        self.save();
+    }
+
+    /// The tab last seen focused.
+    pub fn focused(&self) -> Option<&str> {
+        self.focused.as_ref().map(|(name, _)| name.as_str())
+    }
+
+    pub fn get(&self, name: &str) -> TabStats {
+        self.tabs.get(name).copied().unwrap_or_default()
+    }
+}
+
+/// `3h 12m`, `5m` or `40s`.
+pub fn format_duration(secs: i64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
@@ -0,0 +1,44 @@
+use crate::host;
+use crate::worker;
+use std::collections::BTreeSet;
+
+/// A set of tab names saved in `/data`, so every instance of the plugin sees the same tabs
+/// pinned or protected.
+#[derive(Default)]
+pub struct TabSet {
+    path: &'static str,
+    names: BTreeSet<String>,
+}
+
+impl TabSet {
+    pub fn load(path: &'static str) -> Self {
+        let names = host::read_to_string(path)
+            .map(|contents| contents.lines().map(String::from).collect())
+            .unwrap_or_default();
+
+        TabSet { path, names }
+    }
+
+    pub fn save(&self) {
+        let contents: Vec<&str> = self.names.iter().map(String::as_str).collect();
+        worker::write(self.path, &contents.join("\n"));
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    pub fn toggle(&mut self, name: &str) {
+        if !self.names.remove(name) {
+            self.names.insert(name.to_string());
+        }
+    }
+
+    /// Follows a tab to its new name, since the set only knows tabs by name.
+    pub fn rename(&mut self, old_name: &str, new_name: &str) {
+        if self.names.remove(old_name) {
+            self.names.insert(new_name.to_string());
+            self.save();
+        }
+    }
+}
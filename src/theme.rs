@@ -0,0 +1,220 @@
+use crate::color::Color;
+use crate::contrast::{self, Rgb};
+use owo_colors::{AnsiColors, DynColors, OwoColorize};
+use zellij_tile::prelude::{Palette, PaletteColor};
+
+/// What a color is used for. Each can be set on its own, `<name>_color = "#rrggbb"`, on top of
+/// the preset.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// The selected row's background.
+    Highlight,
+    /// The active tab's name.
+    Active,
+    Prompt,
+    Session,
+    Toast,
+    Warning,
+    Error,
+    ListBadge,
+    GlobalBadge,
+    PaletteBadge,
+    /// The cheatsheet's and the snippet's.
+    HelpBadge,
+    PanesBadge,
+    ProjectsBadge,
+}
+
+impl Role {
+    pub const ALL: [Role; 13] = [
+        Role::Highlight,
+        Role::Active,
+        Role::Prompt,
+        Role::Session,
+        Role::Toast,
+        Role::Warning,
+        Role::Error,
+        Role::ListBadge,
+        Role::GlobalBadge,
+        Role::PaletteBadge,
+        Role::HelpBadge,
+        Role::PanesBadge,
+        Role::ProjectsBadge,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Role::Highlight => "highlight",
+            Role::Active => "active",
+            Role::Prompt => "prompt",
+            Role::Session => "session",
+            Role::Toast => "toast",
+            Role::Warning => "warning",
+            Role::Error => "error",
+            Role::ListBadge => "list_badge",
+            Role::GlobalBadge => "global_badge",
+            Role::PaletteBadge => "palette_badge",
+            Role::HelpBadge => "help_badge",
+            Role::PanesBadge => "panes_badge",
+            Role::ProjectsBadge => "projects_badge",
+        }
+    }
+
+    /// The preset's accent it's drawn in.
+    fn accent(self) -> Color {
+        match self {
+            Role::Highlight | Role::Prompt | Role::ProjectsBadge => Color::Cyan,
+            Role::Active | Role::Error | Role::PanesBadge => Color::Red,
+            Role::Session | Role::GlobalBadge => Color::Magenta,
+            Role::Toast | Role::HelpBadge => Color::Green,
+            Role::Warning | Role::PaletteBadge => Color::Yellow,
+            Role::ListBadge => Color::Blue,
+        }
+    }
+}
+
+/// `theme = "nord"`, the accents every role (and every tab color) is drawn in.
+#[derive(Clone, Copy, Default)]
+pub enum Preset {
+    /// Zellij's own theme, or the terminal's colors until zellij says what it is.
+    #[default]
+    Zellij,
+    Gruvbox,
+    Catppuccin,
+    Nord,
+}
+
+impl Preset {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "zellij" => Some(Preset::Zellij),
+            "gruvbox" => Some(Preset::Gruvbox),
+            "catppuccin" => Some(Preset::Catppuccin),
+            "nord" => Some(Preset::Nord),
+            _ => None,
+        }
+    }
+
+    /// Red, green, yellow, blue, magenta and cyan, in the order of [`Color::ALL`].
+    fn accents(self) -> Option<[Rgb; 6]> {
+        match self {
+            Preset::Zellij => None,
+            Preset::Gruvbox => Some([
+                (251, 73, 52),
+                (184, 187, 38),
+                (250, 189, 47),
+                (131, 165, 152),
+                (211, 134, 155),
+                (142, 192, 124),
+            ]),
+            // mocha's
+            Preset::Catppuccin => Some([
+                (243, 139, 168),
+                (166, 227, 161),
+                (249, 226, 175),
+                (137, 180, 250),
+                (203, 166, 247),
+                (148, 226, 213),
+            ]),
+            Preset::Nord => Some([
+                (191, 97, 106),
+                (163, 190, 140),
+                (235, 203, 139),
+                (129, 161, 193),
+                (180, 142, 173),
+                (136, 192, 208),
+            ]),
+        }
+    }
+}
+
+/// The color of every role, along with what it looks like so text drawn on it stays readable.
+#[derive(Clone, Copy)]
+struct Paint {
+    color: DynColors,
+    rgb: Rgb,
+}
+
+impl Paint {
+    fn rgb(rgb: Rgb) -> Self {
+        Paint {
+            color: DynColors::Rgb(rgb.0, rgb.1, rgb.2),
+            rgb,
+        }
+    }
+}
+
+pub struct Theme {
+    roles: [Paint; 13],
+    accents: [Paint; 6],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::new(Preset::default(), &[], None)
+    }
+}
+
+impl Theme {
+    /// The preset's colors under `overrides`. `palette` is zellij's theme, for the zellij preset.
+    pub fn new(preset: Preset, overrides: &[(Role, Rgb)], palette: Option<&Palette>) -> Self {
+        let accents = Color::ALL.map(|color| match (preset.accents(), palette) {
+            (Some(accents), _) => Paint::rgb(accents[color as usize]),
+            (None, Some(palette)) => Paint::rgb(contrast::rgb(palette_color(palette, color))),
+            (None, None) => ansi(color),
+        });
+        let roles = Role::ALL.map(|role| {
+            overrides
+                .iter()
+                .find(|(overridden, _)| *overridden == role)
+                .map(|(_, rgb)| Paint::rgb(*rgb))
+                .unwrap_or(accents[role.accent() as usize])
+        });
+
+        Theme { roles, accents }
+    }
+
+    /// `text` in the color of `role`.
+    pub fn paint(&self, text: &str, role: Role) -> String {
+        text.color(self.roles[role as usize].color).to_string()
+    }
+
+    /// `text` in the preset's take on a tab color.
+    pub fn accent(&self, text: &str, color: Color) -> String {
+        text.color(self.accents[color as usize].color).to_string()
+    }
+
+    /// `text` on the color of `role`, in whichever of black or white reads best on it.
+    pub fn fill(&self, text: &str, role: Role) -> String {
+        let Paint { color, rgb } = self.roles[role as usize];
+        let (red, green, blue) = contrast::readable_on(rgb);
+        text.truecolor(red, green, blue).on_color(color).to_string()
+    }
+}
+
+fn palette_color(palette: &Palette, color: Color) -> PaletteColor {
+    match color {
+        Color::Red => palette.red,
+        Color::Green => palette.green,
+        Color::Yellow => palette.yellow,
+        Color::Blue => palette.blue,
+        Color::Magenta => palette.magenta,
+        Color::Cyan => palette.cyan,
+    }
+}
+
+/// The terminal's own color, which looks like xterm's as far as contrast goes.
+fn ansi(color: Color) -> Paint {
+    let (ansi, index) = match color {
+        Color::Red => (AnsiColors::Red, 1),
+        Color::Green => (AnsiColors::Green, 2),
+        Color::Yellow => (AnsiColors::Yellow, 3),
+        Color::Blue => (AnsiColors::Blue, 4),
+        Color::Magenta => (AnsiColors::Magenta, 5),
+        Color::Cyan => (AnsiColors::Cyan, 6),
+    };
+    Paint {
+        color: DynColors::Ansi(ansi),
+        rgb: contrast::rgb(PaletteColor::EightBit(index)),
+    }
+}
@@ -0,0 +1,113 @@
+use crate::host;
+use crate::worker;
+use chrono::{Local, TimeZone};
+use std::collections::BTreeMap;
+
+/// A unix timestamp per tab name saved in `/data`, like when each tab was first seen.
+#[derive(Default)]
+pub struct Timestamps {
+    path: &'static str,
+    times: BTreeMap<String, i64>,
+}
+
+impl Timestamps {
+    pub fn load(path: &'static str) -> Self {
+        let times = host::read_to_string(path)
+            .map(|contents| {
+                contents
+                    .lines()
+                    .filter_map(|line| {
+                        let (time, name) = line.split_once('\t')?;
+                        Some((name.to_string(), time.parse().ok()?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Timestamps { path, times }
+    }
+
+    pub fn save(&self) {
+        let contents: Vec<String> = self
+            .times
+            .iter()
+            .map(|(name, time)| format!("{}\t{}", time, name))
+            .collect();
+        worker::write(self.path, &contents.join("\n"));
+    }
+
+    pub fn get(&self, name: &str) -> Option<i64> {
+        self.times.get(name).copied()
+    }
+
+    pub fn set(&mut self, name: &str, time: i64) {
+        self.times.insert(name.to_string(), time);
+    }
+
+    /// Records `time` for `name` unless it already has one, returns whether it didn't.
+    pub fn insert_if_absent(&mut self, name: &str, time: i64) -> bool {
+        if self.times.contains_key(name) {
+            return false;
+        }
+
+        self.times.insert(name.to_string(), time);
+        true
+    }
+
+    /// Follows a tab to its new name, since timestamps only know tabs by name.
+    pub fn rename(&mut self, old_name: &str, new_name: &str) {
+        if let Some(time) = self.times.remove(old_name) {
+            self.times.insert(new_name.to_string(), time);
+            self.save();
+        }
+    }
+}
+
+/// `40s ago`, `2m ago`, `3h ago` or `5d ago`, only the largest unit since it's read at a glance.
+pub fn format_ago(secs: i64) -> String {
+    match secs.max(0) {
+        secs @ 0..=59 => format!("{}s ago", secs),
+        secs @ 60..=3599 => format!("{}m ago", secs / 60),
+        secs @ 3600..=86399 => format!("{}h ago", secs / 3600),
+        secs => format!("{}d ago", secs / 86400),
+    }
+}
+
+/// How times are shown, `time_format = "relative"` by default.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum TimeFormat {
+    /// `5m ago`.
+    #[default]
+    Relative,
+    /// `14:02`, or `Oct 12` for an earlier day.
+    Absolute,
+    Hidden,
+}
+
+impl TimeFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "relative" => Some(TimeFormat::Relative),
+            "absolute" => Some(TimeFormat::Absolute),
+            "hidden" => Some(TimeFormat::Hidden),
+            _ => None,
+        }
+    }
+
+    /// `time` (a unix timestamp) as seen at `now`, nothing when times are hidden.
+    pub fn format(self, time: i64, now: i64) -> Option<String> {
+        match self {
+            TimeFormat::Relative => Some(format_ago(now - time)),
+            TimeFormat::Absolute => {
+                let time = Local.timestamp_opt(time, 0).single()?;
+                let now = Local.timestamp_opt(now, 0).single()?;
+                Some(if time.date_naive() == now.date_naive() {
+                    time.format("%H:%M").to_string()
+                } else {
+                    time.format("%b %d").to_string()
+                })
+            }
+            TimeFormat::Hidden => None,
+        }
+    }
+}
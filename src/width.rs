@@ -0,0 +1,55 @@
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// How many terminal columns `text` takes up, CJK characters and most emoji take two.
+pub fn width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+/// Cuts `text` down to at most `max` columns, ending it with an ellipsis when anything had to
+/// go. A double-width character is never split in half.
+pub fn truncate(text: &str, max: usize) -> String {
+    if width(text) <= max {
+        return text.to_string();
+    }
+
+    let mut truncated = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let c_width = c.width().unwrap_or(0);
+        // keep a column for the ellipsis
+        if used + c_width + 1 > max {
+            break;
+        }
+        truncated.push(c);
+        used += c_width;
+    }
+
+    if max > 0 {
+        truncated.push('…');
+    }
+    truncated
+}
+
+/// Truncates `text` to exactly `columns` columns and pads it with spaces up to that width, so
+/// a background color spans the whole row.
+pub fn fit(text: &str, columns: usize) -> String {
+    let mut fitted = truncate(text, columns);
+    let padding = columns.saturating_sub(width(&fitted));
+    fitted.extend(std::iter::repeat_n(' ', padding));
+    fitted
+}
+
+/// Like [`width`], for text that's already been colored: the escape codes take no columns.
+pub fn visible_width(text: &str) -> usize {
+    let mut visible = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // `ESC [ ... m`, skip up to the letter ending it
+            chars.by_ref().find(char::is_ascii_alphabetic);
+        } else {
+            visible += c.width().unwrap_or(0);
+        }
+    }
+    visible
+}
@@ -0,0 +1,55 @@
+use crate::host;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use zellij_tile::prelude::*;
+
+/// What the plugin calls the worker, `register_worker!` names it with a `_worker` suffix.
+const NAME: &str = "writer";
+/// The name of the `CustomMessage` the worker sends when a file can't be written, its payload
+/// says which and why.
+pub const WRITE_FAILED: &str = "write_failed";
+
+thread_local! {
+    /// The files saved since the last flush, by path, only the last contents of each matter.
+    static PENDING: RefCell<BTreeMap<String, String>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Writes room's files in `/data` on a thread of its own, so a slow disk never holds up
+/// `update` or `render`. Files are written in the order they're posted in.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Writer;
+
+impl ZellijWorker<'_> for Writer {
+    /// `message` is the file's path and `payload` its new contents.
+    fn on_message(&mut self, path: String, contents: String) {
+        if let Err(error) = host::write(&path, contents) {
+            let error = format!("couldn't save {}: {}", path, error);
+            host::post_message_to_plugin(PluginMessage::new_to_plugin(WRITE_FAILED, &error));
+        }
+    }
+}
+
+/// Replaces the file at `path` with `contents` with the next [`flush`], so a burst of keys
+/// saving the same file writes it once. Commands need nothing of the kind, zellij already runs
+/// them in the background and reports how they went as events.
+pub fn write(path: &str, contents: &str) {
+    PENDING.with(|pending| {
+        pending
+            .borrow_mut()
+            .insert(path.to_string(), contents.to_string())
+    });
+}
+
+/// Whether files were saved since the last flush.
+pub fn pending() -> bool {
+    PENDING.with(|pending| !pending.borrow().is_empty())
+}
+
+/// Hands every file saved since the last flush to the worker.
+pub fn flush() {
+    let pending = PENDING.with(|pending| std::mem::take(&mut *pending.borrow_mut()));
+    for (path, contents) in pending {
+        host::post_message_to(PluginMessage::new_to_worker(NAME, &path, &contents));
+    }
+}
@@ -0,0 +1,336 @@
+//! Room driven through [`App`] the way zellij drives it, by events made up for each test, with
+//! what it drew and the commands it issued checked afterwards.
+#![cfg(not(target_arch = "wasm32"))]
+
+use room::{host, App, State};
+use std::collections::BTreeMap;
+use zellij_tile::prelude::{
+    CharOrArrow, Event, EventType, Key, PermissionStatus, PluginCommand, TabInfo,
+};
+
+const ROWS: usize = 20;
+const COLS: usize = 60;
+
+/// A room with its permissions granted, on a machine where room ran before: its files hold
+/// nothing but the onboarding page having been seen.
+struct Room {
+    state: State,
+}
+
+impl Room {
+    /// Loaded with `options`, listing `tabs` with the first one active.
+    fn open(options: &[(&str, &str)], tabs: &[&str]) -> Self {
+        Self::open_with_files(&["/data/onboarded"], options, tabs)
+    }
+
+    /// Like [`Room::open`], with none of room's files around, as on a machine it never ran on.
+    fn open_fresh(options: &[(&str, &str)], tabs: &[&str]) -> Self {
+        Self::open_with_files(&[], options, tabs)
+    }
+
+    /// Starts with only `files` (empty ones) in room's data folder.
+    fn open_with_files(files: &[&str], options: &[(&str, &str)], tabs: &[&str]) -> Self {
+        host::remove_files();
+        for path in files {
+            host::write(path, "").unwrap();
+        }
+        let mut room = Room {
+            state: State::default(),
+        };
+        let configuration = options
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<BTreeMap<_, _>>();
+        room.state.load(configuration);
+        room.state
+            .update(Event::PermissionRequestResult(PermissionStatus::Granted));
+        room.tabs(tabs);
+        host::take_commands();
+        room
+    }
+
+    fn tabs(&mut self, names: &[&str]) {
+        let tabs = names
+            .iter()
+            .enumerate()
+            .map(|(position, name)| TabInfo {
+                position,
+                name: name.to_string(),
+                active: position == 0,
+                ..Default::default()
+            })
+            .collect();
+        self.state.update(Event::TabUpdate(tabs));
+    }
+
+    fn press(&mut self, key: Key) -> bool {
+        self.state.update(Event::Key(key))
+    }
+
+    fn type_text(&mut self, text: &str) {
+        for character in text.chars() {
+            self.press(Key::Char(character));
+        }
+    }
+
+    /// The frame's lines without their colors, the way they read on screen.
+    fn screen(&mut self) -> Vec<String> {
+        self.screen_of(ROWS)
+    }
+
+    /// Like [`Room::screen`], in a pane of `rows`.
+    fn screen_of(&mut self, rows: usize) -> Vec<String> {
+        strip_escapes(&self.state.render(rows, COLS))
+            .lines()
+            .map(|line| line.trim_end().to_string())
+            .collect()
+    }
+
+    /// The tabs listed, `2:server`, in order.
+    fn listed(&mut self) -> Vec<String> {
+        self.screen()
+            .into_iter()
+            .filter(|line| line.chars().next().is_some_and(|c| c.is_ascii_digit()))
+            .collect()
+    }
+
+    /// The mode named at the top left, `tabs` or `tree`.
+    fn mode(&mut self) -> String {
+        let screen = self.screen();
+        let first = screen.iter().find(|line| !line.is_empty()).unwrap();
+        first.split_whitespace().next().unwrap().to_string()
+    }
+}
+
+fn strip_escapes(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(character) = chars.next() {
+        if character == '\x1b' {
+            // CSI sequences end on their first letter
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            stripped.push(character);
+        }
+    }
+    stripped
+}
+
+fn alt(character: char) -> Key {
+    Key::Alt(CharOrArrow::Char(character))
+}
+
+/// The tab (1 based) the commands switched to, if any.
+fn switched_to(commands: &[PluginCommand]) -> Option<u32> {
+    commands.iter().find_map(|command| match command {
+        PluginCommand::SwitchTabTo(tab) => Some(*tab),
+        _ => None,
+    })
+}
+
+fn closed(commands: &[PluginCommand]) -> bool {
+    commands
+        .iter()
+        .any(|command| matches!(command, PluginCommand::CloseFocus))
+}
+
+#[test]
+fn lists_every_tab() {
+    let mut room = Room::open(&[], &["editor", "server", "logs"]);
+    assert_eq!(room.listed(), ["1:editor", "2:server", "3:logs"]);
+    assert_eq!(room.mode(), "tabs");
+}
+
+#[test]
+fn the_first_instance_explains_itself_until_a_key() {
+    let mut room = Room::open_fresh(&[], &["editor", "server"]);
+    assert!(room.listed().is_empty());
+    assert!(room.screen().iter().any(|line| line.starts_with("welcome")));
+
+    room.press(Key::Null);
+    assert_eq!(room.listed(), ["1:editor", "2:server"]);
+}
+
+#[test]
+fn filtering_narrows_the_list_and_backspace_widens_it() {
+    let mut room = Room::open(&[], &["editor", "server", "logs"]);
+
+    room.type_text("er");
+    assert_eq!(room.listed(), ["2:server", "1:editor"]);
+
+    room.type_text("v");
+    assert_eq!(room.listed(), ["2:server"]);
+
+    room.press(Key::Backspace);
+    room.press(Key::Backspace);
+    room.press(Key::Backspace);
+    assert_eq!(room.listed().len(), 3);
+}
+
+#[test]
+fn a_filter_matching_nothing_says_so() {
+    let mut room = Room::open(&[], &["editor", "server"]);
+    room.type_text("zzz");
+    assert!(room.listed().is_empty());
+    assert!(room
+        .screen()
+        .iter()
+        .any(|line| line.contains("no tabs match 'zzz'")));
+}
+
+#[test]
+fn a_filtered_tab_is_switched_to_with_enter() {
+    let mut room = Room::open(&[], &["editor", "server", "logs"]);
+    room.type_text("logs");
+    room.press(Key::Char('\n'));
+
+    let commands = host::take_commands();
+    assert!(closed(&commands));
+    assert_eq!(switched_to(&commands), Some(3));
+}
+
+#[test]
+fn an_index_filters_by_position() {
+    let mut room = Room::open(&[], &["editor", "server", "logs"]);
+    room.type_text("2");
+    assert_eq!(room.listed(), ["2:server"]);
+}
+
+#[test]
+fn the_selection_moves_with_the_arrows() {
+    let mut room = Room::open(&[], &["editor", "server", "logs"]);
+    room.press(Key::Down);
+    room.press(Key::Down);
+    room.press(Key::Up);
+    room.press(Key::Char('\n'));
+
+    assert_eq!(switched_to(&host::take_commands()), Some(2));
+}
+
+#[test]
+fn end_and_home_select_the_ends_of_the_list() {
+    let mut room = Room::open(&[], &["editor", "server", "logs", "notes"]);
+    room.press(Key::End);
+    room.press(Key::Char('\n'));
+    assert_eq!(switched_to(&host::take_commands()), Some(4));
+
+    let mut room = Room::open(&[], &["editor", "server", "logs", "notes"]);
+    room.press(Key::End);
+    room.press(Key::Home);
+    room.press(Key::Char('\n'));
+    assert_eq!(switched_to(&host::take_commands()), Some(1));
+}
+
+#[test]
+fn esc_clears_the_filter_before_closing() {
+    let mut room = Room::open(&[], &["editor", "server"]);
+    room.type_text("serv");
+
+    room.press(Key::Esc);
+    assert!(!closed(&host::take_commands()));
+    assert_eq!(room.listed().len(), 2);
+
+    room.press(Key::Esc);
+    assert!(closed(&host::take_commands()));
+}
+
+#[test]
+fn tree_mode_opens_with_its_key_and_esc_goes_back() {
+    let mut room = Room::open(&[], &["editor", "server"]);
+    room.press(alt('t'));
+    assert_eq!(room.mode(), "tree");
+
+    room.press(Key::Esc);
+    assert_eq!(room.mode(), "tabs");
+    assert!(!closed(&host::take_commands()));
+}
+
+#[test]
+fn cycling_modes_goes_around_to_the_list() {
+    let mut room = Room::open(&[], &["editor", "server"]);
+    let mut modes = Vec::new();
+    for _ in 0..6 {
+        room.press(alt('m'));
+        modes.push(room.mode());
+    }
+    assert_eq!(
+        modes,
+        ["sessions", "actions", "keys", "panes", "tree", "tabs"]
+    );
+}
+
+#[test]
+fn sessions_are_followed_only_while_global_mode_is_open() {
+    let mut room = Room::open(&[], &["editor"]);
+    room.press(Key::Ctrl('g'));
+    assert_eq!(room.mode(), "sessions");
+    assert!(host::take_commands().iter().any(|command| matches!(
+        command,
+        PluginCommand::Subscribe(events) if events.contains(&EventType::SessionUpdate)
+    )));
+
+    room.press(Key::Esc);
+    assert!(host::take_commands().iter().any(|command| matches!(
+        command,
+        PluginCommand::Unsubscribe(events) if events.contains(&EventType::SessionUpdate)
+    )));
+}
+
+#[test]
+fn a_trailing_comma_in_ignore_hides_only_what_it_names() {
+    let mut room = Room::open(&[("ignore", "^tmp,")], &["editor", "tmp1", "server"]);
+    assert_eq!(room.listed(), ["1:editor", "3:server"]);
+}
+
+#[test]
+fn a_trailing_comma_in_protected_protects_only_what_it_names() {
+    let mut room = Room::open(&[("protected", "^prod,")], &["editor", "prod", "server"]);
+    room.press(Key::Ctrl('d'));
+    assert!(host::take_commands()
+        .iter()
+        .any(|command| matches!(command, PluginCommand::CloseFocusedTab)));
+
+    room.press(Key::Down);
+    room.press(Key::Ctrl('d'));
+    assert!(room.screen().iter().any(|line| line.contains("protected")));
+}
+
+#[test]
+fn the_tab_menu_scrolls_to_its_selection_in_a_short_pane() {
+    let mut room = Room::open(&[], &["editor", "server"]);
+    room.press(Key::Ctrl('a'));
+    room.press(Key::Up);
+
+    let screen = room.screen_of(6);
+    assert!(screen.len() <= 6);
+    assert_eq!(screen.last().unwrap(), "merge into the current tab");
+}
+
+#[test]
+fn an_earlier_timer_leaves_the_toast_up() {
+    let mut room = Room::open(&[("confirm_toast_ms", "5000")], &["editor", "server"]);
+    room.press(Key::Down);
+    room.press(Key::Char('\n'));
+    assert!(room
+        .screen()
+        .iter()
+        .any(|line| line.contains("switched to 2: server")));
+
+    // the flush's, a second in
+    room.state.update(Event::Timer(1.0));
+    assert!(switched_to(&host::take_commands()).is_none());
+
+    room.press(Key::Null);
+    assert_eq!(switched_to(&host::take_commands()), Some(2));
+}
+
+#[test]
+fn a_rename_made_elsewhere_keeps_the_selection() {
+    let mut room = Room::open(&[], &["editor", "server", "logs"]);
+    room.press(Key::Down);
+    room.tabs(&["editor", "api", "logs"]);
+    assert_eq!(room.listed(), ["1:editor", "2:api", "3:logs"]);
+
+    room.press(Key::Char('\n'));
+    assert_eq!(switched_to(&host::take_commands()), Some(2));
+}